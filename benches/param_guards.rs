@@ -0,0 +1,63 @@
+//! Measures `apply_float_param_guards` cost as the number of machines grows.
+//! Run with `cargo bench --bench param_guards`.
+
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use bevy_gearbox::{prelude::*, GearboxPlugin};
+
+struct Health;
+
+const FRAMES: u32 = 200;
+
+fn build_app(machines: usize) -> (App, Vec<Entity>) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(GearboxPlugin);
+    app.add_systems(Update, apply_float_param_guards::<Health>);
+
+    let mut roots = Vec::with_capacity(machines);
+    for _ in 0..machines {
+        let root = app.world_mut().spawn(FloatParam::<Health>::new(100.0)).id();
+        let alive = app.world_mut().spawn(StateChildOf(root)).id();
+        let dead = app.world_mut().spawn(StateChildOf(root)).id();
+        app.world_mut().spawn((
+            Source(alive),
+            Target(dead),
+            AlwaysEdge,
+            FloatInRange::<Health>::new(f32::MIN, 0.0, 0.0),
+        ));
+        app.world_mut().entity_mut(root).insert((InitialState(alive), StateMachine::new()));
+        roots.push(root);
+    }
+    // Settle initialization and the first full guard pass
+    app.update();
+    app.update();
+    (app, roots)
+}
+
+/// Average frame time when `changed_per_frame` machines touch their parameter each frame.
+fn measure(machines: usize, changed_per_frame: usize) -> Duration {
+    let (mut app, roots) = build_app(machines);
+    let mut total = Duration::ZERO;
+    for frame in 0..FRAMES {
+        for i in 0..changed_per_frame {
+            let root = roots[(frame as usize * changed_per_frame + i) % roots.len()];
+            let value = 50.0 + (frame % 2) as f32;
+            app.world_mut().get_mut::<FloatParam<Health>>(root).unwrap().set(value);
+        }
+        let start = Instant::now();
+        app.update();
+        total += start.elapsed();
+    }
+    total / FRAMES
+}
+
+fn main() {
+    println!("{:>10} {:>14} {:>14}", "machines", "idle", "1% changed");
+    for machines in [100, 1_000, 10_000, 50_000] {
+        let idle = measure(machines, 0);
+        let changed = measure(machines, (machines / 100).max(1));
+        println!("{:>10} {:>14?} {:>14?}", machines, idle, changed);
+    }
+}
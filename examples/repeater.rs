@@ -14,9 +14,8 @@ fn main() {
         //.add_plugins(EguiPlugin::default())
         //.add_plugins(WorldInspectorPlugin::new())
         .add_systems(Startup, setup)
-        .add_systems(Update, (input_system, repeater_system))
+        .add_systems(Update, input_system)
         .add_observer(print_enter_state_messages)
-        .add_observer(reset_repeater_on_cast)
         .run();
 }
 
@@ -26,12 +25,8 @@ fn main() {
 #[derive(Component)]
 struct AbilityMachine;
 
-/// A component to manage the repeater's state.
-#[derive(Component)]
-struct Repeater {
-    timer: Timer,
-    remaining: u32,
-}
+/// How many projectiles a cast fires.
+const REPETITIONS: u32 = 5;
 
 // --- Event to trigger state transitions ---
 #[derive(SimpleTransition, EntityEvent, Clone)]
@@ -51,6 +46,7 @@ fn setup(mut commands: Commands) {
         let repeating = world.spawn(()).id();
         let ready_cast_ability = world.spawn(()).id();
         let repeating_on_complete = world.spawn(()).id();
+        let repeating_fire = world.spawn(()).id();
 
         world.entity_mut(machine_entity).insert((
             Name::new("AbilityStateMachine"),
@@ -67,10 +63,6 @@ fn setup(mut commands: Commands) {
         world.entity_mut(repeating).insert((
             Name::new("Repeating"),
             StateChildOf(machine_entity),
-            Repeater {
-                timer: Timer::new(Duration::from_secs(1), TimerMode::Repeating),
-                remaining: 5,
-            },
         ));
 
         world.entity_mut(ready_cast_ability).insert((
@@ -88,6 +80,17 @@ fn setup(mut commands: Commands) {
             EdgeKind::External,
             Source(repeating),
         ));
+
+        // Fires every second while `Repeating` is active. Being internal, it doesn't
+        // re-enter `Repeating`, so the repetition count carries on until it is exited.
+        world.entity_mut(repeating_fire).insert((
+            Name::new("Repeating -> Repeating (Every 1s)"),
+            Target(repeating),
+            Every::new(Duration::from_secs(1)).with_max_repetitions(REPETITIONS),
+            EdgeKind::Internal,
+            Source(repeating),
+        ))
+        .observe(fire_projectile);
     });
 }
 
@@ -105,43 +108,24 @@ fn input_system(
     }
 }
 
-/// The core logic for the repeater. Ticks the timer and fires "projectiles".
-fn repeater_system(
-    mut q_repeater: Query<(Entity, &mut Repeater), With<Active>>,
-    q_child_of: Query<&bevy_gearbox::StateChildOf>,
-    time: Res<Time>,
+/// Runs each time the `Every` edge fires. Fires a "projectile", and once the last one
+/// is out sends `OnComplete` so the chart goes back to `Ready`.
+fn fire_projectile(
+    transition_actions: On<TransitionActions>,
+    q_timer: Query<&EveryTimer>,
+    q_source: Query<&Source>,
+    q_child_of: Query<&StateChildOf>,
     mut commands: Commands,
 ) {
-    // This system only runs when the machine is in the `Repeating` state.
-    for (entity, mut repeater) in q_repeater.iter_mut() {
-        repeater.timer.tick(time.delta());
-        if repeater.timer.just_finished() {
-            if repeater.remaining > 0 {
-                println!("   => PEW! ({} remaining)", repeater.remaining - 1);
-                repeater.remaining -= 1;
-            }
-
-            let root_entity = q_child_of.root_ancestor(entity);
-
-            if repeater.remaining == 0 {
-                // The repeater is done. Fire the `OnComplete` event on the `Repeating`
-                // state entity. The `EventEdge` on that entity will handle
-                // transitioning back to the `Ready` state.
-                commands.trigger(OnComplete { target: root_entity });
-            }
-        }
-    }
-}
-
-/// When we re-enter the 'Ready' state, reset the repeater's values.
-fn reset_repeater_on_cast(
-    exit_state: On<ExitState>,
-    mut q_repeater: Query<&mut Repeater>,
-) {
-    let target = exit_state.target;
-    if let Ok(mut repeater) = q_repeater.get_mut(target) {
-        repeater.remaining = 5;
-        repeater.timer.reset();
+    let edge = transition_actions.target;
+    let Ok(timer) = q_timer.get(edge) else { return };
+    let remaining = REPETITIONS - timer.repetitions();
+    println!("   => PEW! ({} remaining)", remaining);
+
+    if remaining == 0 {
+        let Ok(Source(repeating)) = q_source.get(edge) else { return };
+        let root_entity = q_child_of.root_ancestor(*repeating);
+        commands.trigger(OnComplete { target: root_entity });
     }
 }
 
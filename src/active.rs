@@ -15,9 +15,11 @@ pub(crate) fn add_active(
     let target = enter_state.target;
     commands.queue(move |world: &mut World| {
         let root = world.query::<&StateChildOf>().query(world).root_ancestor(target);
-        let Some(state_machine) = world.entity(root).get::<StateMachine>() else { return; };
+        let Some(state_machine) = world.get::<StateMachine>(root) else { return; };
         if state_machine.active.contains(&target) {
-            world.entity_mut(target).remove::<Inactive>().insert(Active);
+            if let Ok(mut entity) = world.get_entity_mut(target) {
+                entity.remove::<Inactive>().insert(Active);
+            }
         }
     });
 }
@@ -29,9 +31,11 @@ pub(crate) fn add_inactive(
     let target = exit_state.target;
     commands.queue(move |world: &mut World| {
         let root = world.query::<&StateChildOf>().query(world).root_ancestor(target);
-        let Some(state_machine) = world.entity(root).get::<StateMachine>() else { return; };
+        let Some(state_machine) = world.get::<StateMachine>(root) else { return; };
         if !state_machine.active.contains(&target) {
-            world.entity_mut(target).remove::<Active>().insert(Inactive);
+            if let Ok(mut entity) = world.get_entity_mut(target) {
+                entity.remove::<Active>().insert(Inactive);
+            }
         }
     });
 }
\ No newline at end of file
@@ -1,5 +1,44 @@
-use bevy::{prelude::*, state::state::FreelyMutableState};
-use crate::EnterState;
+use std::marker::PhantomData;
+
+use bevy::{platform::collections::HashMap, prelude::*, state::state::FreelyMutableState};
+use crate::{chart_states::ChartStates, transitions::{EdgeKind, Target}, EnterState, ExitState, Transition};
+
+/// Configuration of a chart <-> Bevy `States` bridge for `S`.
+#[derive(Resource, Clone, Debug)]
+pub struct StateBridge<S: States> {
+    /// Also move bridged charts to the node carrying `State<S>` when other code changes it.
+    pub follow_state: bool,
+    /// Kind of the edge from the machine root to that node when following `State<S>`.
+    pub edge_kind: EdgeKind,
+    /// Value to set when a machine leaves all of its nodes carrying `S`.
+    pub fallback: Option<S>,
+}
+
+impl<S: States> Default for StateBridge<S> {
+    fn default() -> Self {
+        Self { follow_state: false, edge_kind: EdgeKind::Internal, fallback: None }
+    }
+}
+
+impl<S: States> StateBridge<S> {
+    pub fn new() -> Self { Self::default() }
+
+    /// Bridge both ways: chart entries set `NextState<S>`, and `State<S>` changes move the chart.
+    pub fn two_way(mut self) -> Self {
+        self.follow_state = true;
+        self
+    }
+
+    pub fn with_edge_kind(mut self, edge_kind: EdgeKind) -> Self {
+        self.edge_kind = edge_kind;
+        self
+    }
+
+    pub fn with_fallback(mut self, fallback: S) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+}
 
 /// Bridge a Gearbox chart's EnterState events to Bevy `States`,
 /// setting `NextState<S>` when a chart node carrying `S` is entered.
@@ -7,6 +46,18 @@ pub trait AppBevyStateBridgeExt {
     fn add_state_bridge<S>(&mut self) -> &mut Self
     where
         S: States + FreelyMutableState + Component + Clone + 'static;
+
+    /// Like `add_state_bridge`, with reverse following and exit fallback configured by `bridge`.
+    fn add_state_bridge_with<S>(&mut self, bridge: StateBridge<S>) -> &mut Self
+    where
+        S: States + FreelyMutableState + Component + Clone + 'static;
+
+    /// Only the reverse direction: move charts to the node carrying `State<S>` whenever it
+    /// changes. Works for any `States`. `ComputedStates` can only be followed: Bevy derives
+    /// them from their sources, so bridge those instead.
+    fn add_state_follower<S>(&mut self, edge_kind: EdgeKind) -> &mut Self
+    where
+        S: States + Component + Clone + 'static;
 }
 
 impl AppBevyStateBridgeExt for App {
@@ -14,22 +65,138 @@ impl AppBevyStateBridgeExt for App {
     where
         S: States + FreelyMutableState + Component + Clone + 'static,
     {
-        self.add_observer(bridge_chart_to_bevy_state::<S>)
+        self.add_state_bridge_with(StateBridge::<S>::default())
+    }
+
+    fn add_state_bridge_with<S>(&mut self, bridge: StateBridge<S>) -> &mut Self
+    where
+        S: States + FreelyMutableState + Component + Clone + 'static,
+    {
+        let follow_state = bridge.follow_state;
+        let edge_kind = bridge.edge_kind;
+        let has_fallback = bridge.fallback.is_some();
+        self.insert_resource(bridge)
+            .add_observer(bridge_chart_to_bevy_state::<S>);
+        if has_fallback {
+            self.add_observer(mark_bridge_exit::<S>)
+                .add_systems(Update, apply_bridge_fallback::<S>);
+        }
+        if follow_state {
+            self.add_state_follower::<S>(edge_kind);
+        }
+        self
+    }
+
+    fn add_state_follower<S>(&mut self, edge_kind: EdgeKind) -> &mut Self
+    where
+        S: States + Component + Clone + 'static,
+    {
+        self.insert_resource(StateFollower::<S> { edge_kind, skip: None, edges: HashMap::new() })
+            .add_systems(Update, follow_bevy_state::<S>.run_if(state_changed::<S>))
     }
 }
 
 fn bridge_chart_to_bevy_state<S: States + FreelyMutableState + Component + Clone + 'static>(
     enter_state: On<EnterState>,
-    mut next: ResMut<NextState<S>>,
+    next: Option<ResMut<NextState<S>>>,
+    current: Option<Res<State<S>>>,
     q_state: Query<&S>,
 ) {
     let target = enter_state.target;
-    if let Ok(s) = q_state.get(target) {
-        next.set(s.clone());
+    let (Some(mut next), Ok(s)) = (next, q_state.get(target)) else { return; };
+    // Entered because State<S> already holds this value (reverse bridge): don't echo it back
+    let unchanged = matches!(*next, NextState::Unchanged);
+    if unchanged && current.is_some_and(|current| *current.get() == *s) { return; }
+    next.set(s.clone());
+}
+
+/// A machine exited a node carrying `S`; checked for the fallback once its transition settled.
+#[derive(Component)]
+struct BridgeExitPending<S>(PhantomData<S>);
+
+fn mark_bridge_exit<S: States + Component>(
+    exit_state: On<ExitState>,
+    q_state: Query<(), With<S>>,
+    mut commands: Commands,
+) {
+    if !q_state.contains(exit_state.target) { return; }
+    commands.entity(exit_state.state_machine).try_insert(BridgeExitPending::<S>(PhantomData));
+}
+
+fn apply_bridge_fallback<S: States + FreelyMutableState + Component + Clone>(
+    bridge: Res<StateBridge<S>>,
+    q_pending: Query<Entity, With<BridgeExitPending<S>>>,
+    chart_states: ChartStates,
+    next: Option<ResMut<NextState<S>>>,
+    follower: Option<ResMut<StateFollower<S>>>,
+    mut commands: Commands,
+) {
+    let mut fall_back = false;
+    for root in q_pending.iter() {
+        commands.entity(root).remove::<BridgeExitPending<S>>();
+        fall_back |= !chart_states.is_in_state_with::<S>(root);
+    }
+    let (true, Some(fallback), Some(mut next)) = (fall_back, bridge.fallback.clone(), next) else { return; };
+    if let Some(mut follower) = follower {
+        follower.skip = Some(fallback.clone());
+    }
+    next.set(fallback);
+}
+
+#[derive(Resource)]
+struct StateFollower<S: States> {
+    edge_kind: EdgeKind,
+    /// A value set by the bridge itself (the exit fallback), not to be followed.
+    skip: Option<S>,
+    /// Detached edge used to move a machine to each followed node. These edges have no `Source`,
+    /// so they stay out of the root's `Transitions` and everything built on it.
+    edges: HashMap<Entity, Entity>,
+}
+
+fn follow_bevy_state<S: States + Component>(
+    mut follower: ResMut<StateFollower<S>>,
+    current: Option<Res<State<S>>>,
+    q_nodes: Query<(Entity, &S)>,
+    q_edges: Query<(), With<Target>>,
+    chart_states: ChartStates,
+    mut commands: Commands,
+) {
+    let Some(current) = current else { return; };
+    if follower.skip.take().is_some_and(|skip| skip == *current.get()) { return; }
+
+    // Drop edges of nodes that no longer carry `S`
+    follower.edges.retain(|&node, &mut edge| {
+        let keep = q_nodes.contains(node);
+        if !keep {
+            commands.entity(edge).try_despawn();
+        }
+        keep
+    });
+
+    let edge_kind = follower.edge_kind;
+    let mut moved = Vec::new();
+    for (node, value) in q_nodes.iter() {
+        if value != current.get() { continue; }
+        let root = chart_states.root_of(node);
+        if root == node || chart_states.machine(root).is_none() { continue; }
+        if moved.contains(&root) || chart_states.is_in(root, node) { continue; }
+        moved.push(root);
+
+        let edge = match follower.edges.get(&node) {
+            Some(&edge) if q_edges.contains(edge) => edge,
+            _ => {
+                let edge = commands.spawn(Target(node)).id();
+                follower.edges.insert(node, edge);
+                edge
+            }
+        };
+        commands.entity(edge).insert(edge_kind);
+        commands.trigger(Transition { machine: root, source: root, edge, payload: () });
     }
 }
 
 /// Commands helper to emit a transition event to a specific chart root, located by a marker `M`.
+/// Warns and does nothing unless exactly one entity has `M`; see `ChartSendCommandsExt` for broadcasts.
 pub trait GearboxCommandsExt {
     /// Build and emit an EntityEvent using the resolved chart root `Entity` for marker `M`.
     /// Usage: `commands.emit_to_chart::<AppState>(|root| MyEvent::new(root))`.
@@ -45,8 +212,9 @@ impl<'w, 's> GearboxCommandsExt for Commands<'w, 's> {
     {
         self.queue(move |world: &mut World| {
             let mut q = world.query_filtered::<Entity, With<M>>();
-            if let Ok(root) = q.single(world) {
-                make.trigger_into_world(world, root);
+            match q.single(world) {
+                Ok(root) => make.trigger_into_world(world, root),
+                Err(err) => warn!("emit_to_chart::<{}>: {err}; use `send_to_charts` to reach several machines", std::any::type_name::<M>()),
             }
         });
     }
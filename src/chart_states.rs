@@ -0,0 +1,68 @@
+use bevy::{
+    ecs::{archetype::Archetypes, component::Components, entity::Entities, system::SystemParam},
+    prelude::*,
+};
+
+use crate::{StateChildOf, StateMachine};
+
+/// Read-only view of which states machines are in. Usable alongside mutable queries and in
+/// run conditions: `active_with` inspects archetypes rather than reading components.
+#[derive(SystemParam)]
+pub struct ChartStates<'w, 's> {
+    machines: Query<'w, 's, &'static StateMachine>,
+    child_of: Query<'w, 's, &'static StateChildOf>,
+    entities: &'w Entities,
+    archetypes: &'w Archetypes,
+    components: &'w Components,
+}
+
+impl ChartStates<'_, '_> {
+    /// The machine root of `state`.
+    pub fn root_of(&self, state: Entity) -> Entity {
+        self.child_of.root_ancestor(state)
+    }
+
+    pub fn machine(&self, root: Entity) -> Option<&StateMachine> {
+        self.machines.get(root).ok()
+    }
+
+    /// Whether the machine at `root` is currently in `state`.
+    pub fn is_in(&self, root: Entity, state: Entity) -> bool {
+        self.machine(root).is_some_and(|machine| machine.is_active(&state))
+    }
+
+    /// Whether `state` is active in its own machine.
+    pub fn is_active(&self, state: Entity) -> bool {
+        self.is_in(self.root_of(state), state)
+    }
+
+    pub fn active_states(&self, root: Entity) -> impl Iterator<Item = Entity> + '_ {
+        self.machine(root).into_iter().flat_map(|machine| machine.active.iter().copied())
+    }
+
+    pub fn active_leaves(&self, root: Entity) -> impl Iterator<Item = Entity> + '_ {
+        self.machine(root).into_iter().flat_map(|machine| machine.active_leaves.iter().copied())
+    }
+
+    /// Active states of the machine at `root` that carry component `C`.
+    pub fn active_with<C: Component>(&self, root: Entity) -> impl Iterator<Item = Entity> + '_ {
+        let component = self.components.component_id::<C>();
+        self.active_states(root).filter(move |&state| {
+            let (Some(component), Some(location)) = (component, self.entities.get(state)) else { return false; };
+            self.archetypes[location.archetype_id].contains(component)
+        })
+    }
+
+    /// Whether the machine at `root` has an active state carrying component `C`.
+    pub fn is_in_state_with<C: Component>(&self, root: Entity) -> bool {
+        self.active_with::<C>(root).next().is_some()
+    }
+}
+
+/// Run condition: some chart root marked `M` has an active state carrying component `C`.
+/// Usage: `.run_if(in_chart_state::<AppChart, Paused>())`.
+pub fn in_chart_state<M: Component, C: Component>() -> impl FnMut(Query<Entity, With<M>>, ChartStates) -> bool + Clone {
+    |q_roots: Query<Entity, With<M>>, chart_states: ChartStates| {
+        q_roots.iter().any(|root| chart_states.is_in_state_with::<C>(root))
+    }
+}
@@ -0,0 +1,166 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+
+use crate::{
+    repair::{active_exit_order, enter_below, exit_states},
+    transitions::{arm_edge, Source, Target},
+    InitialState, Parallel, StateChildOf, StateChildren, StateMachine,
+};
+
+/// Commands for editing a chart while its machine runs. Each edit keeps `StateMachine`
+/// consistent: states that become reachable under an active parent are entered, states that
+/// leave the active configuration are exited, and edges added to active states are armed.
+pub trait ChartEditCommandsExt {
+    /// Spawn a state under `parent`. It is entered right away if `parent` is an active
+    /// `Parallel` state, or an active leaf whose `InitialState` is the new state.
+    fn add_state(&mut self, parent: Entity, bundle: impl Bundle) -> Entity;
+
+    /// Spawn an edge from `source`. If `source` is active, its `After`/`Every` timer starts
+    /// and an `AlwaysEdge` is checked, as if `source` had just been entered.
+    fn add_edge(&mut self, source: Entity, bundle: impl Bundle) -> Entity;
+
+    /// Move `state` and its subtree under `new_parent`, e.g. to graft a sub-chart onto a
+    /// running machine. An active subtree is exited first; it is entered again under the
+    /// new parent by the same rule as `add_state`.
+    fn reparent_state(&mut self, state: Entity, new_parent: Entity);
+
+    /// Change the initial substate of `state`. An active leaf `state` drills into it right away;
+    /// otherwise it applies on the next entry.
+    fn set_initial_state(&mut self, state: Entity, initial: Entity);
+
+    /// Point `edge` at `target`. A pending `After` timer keeps running.
+    fn set_target(&mut self, edge: Entity, target: Entity);
+
+    /// Make `state` parallel or not. An active state turning parallel enters its inactive
+    /// regions; an active state turning exclusive keeps one region (its `InitialState` if
+    /// active, else the first active child) and exits the others.
+    fn set_parallel(&mut self, state: Entity, parallel: bool);
+}
+
+impl ChartEditCommandsExt for Commands<'_, '_> {
+    fn add_state(&mut self, parent: Entity, bundle: impl Bundle) -> Entity {
+        let state = self.spawn((bundle, StateChildOf(parent))).id();
+        self.queue(move |world: &mut World| enter_if_reachable(world, state));
+        state
+    }
+
+    fn add_edge(&mut self, source: Entity, bundle: impl Bundle) -> Entity {
+        let edge = self.spawn((bundle, Source(source))).id();
+        self.queue(move |world: &mut World| {
+            if let Some(root) = active_machine(world, source) {
+                arm_edge(world, root, edge);
+            }
+        });
+        edge
+    }
+
+    fn reparent_state(&mut self, state: Entity, new_parent: Entity) {
+        self.queue(move |world: &mut World| {
+            if world.get_entity(state).is_err() || world.get_entity(new_parent).is_err() {
+                warn!("reparent_state: {state} or its new parent {new_parent} no longer exists");
+                return;
+            }
+            if let Some(StateChildOf(old_parent)) = world.get::<StateChildOf>(state) {
+                let old_parent = *old_parent;
+                if let Some(root) = active_machine(world, state) {
+                    let exits = subtree_exits(world, root, state);
+                    exit_states(world, root, old_parent, &exits);
+                }
+            }
+            if let Ok(mut state) = world.get_entity_mut(state) {
+                state.insert(StateChildOf(new_parent));
+            }
+            enter_if_reachable(world, state);
+        });
+    }
+
+    fn set_initial_state(&mut self, state: Entity, initial: Entity) {
+        self.queue(move |world: &mut World| {
+            let Ok(mut entity) = world.get_entity_mut(state) else {
+                warn!("set_initial_state: state {state} no longer exists");
+                return;
+            };
+            entity.insert(InitialState(initial));
+            if let Some(root) = active_machine(world, state) {
+                let _ = world.run_system_once_with(enter_below, (root, state, Vec::new()));
+            }
+        });
+    }
+
+    fn set_target(&mut self, edge: Entity, target: Entity) {
+        self.queue(move |world: &mut World| {
+            let Ok(mut entity) = world.get_entity_mut(edge) else {
+                warn!("set_target: edge {edge} no longer exists");
+                return;
+            };
+            entity.insert(Target(target));
+            let Some(source) = world.get::<Source>(edge).map(|source| source.0) else { return; };
+            if let Some(root) = active_machine(world, source) {
+                arm_edge(world, root, edge);
+            }
+        });
+    }
+
+    fn set_parallel(&mut self, state: Entity, parallel: bool) {
+        self.queue(move |world: &mut World| {
+            let children: Vec<Entity> = world.get::<StateChildren>(state)
+                .map(|children| children.into_iter().copied().collect())
+                .unwrap_or_default();
+            let Ok(mut entity) = world.get_entity_mut(state) else {
+                warn!("set_parallel: state {state} no longer exists");
+                return;
+            };
+            if parallel {
+                entity.insert(Parallel);
+            } else {
+                entity.remove::<Parallel>();
+            }
+            let Some(root) = active_machine(world, state) else { return; };
+            let Some(machine) = world.get::<StateMachine>(root) else { return; };
+            let (active, inactive): (Vec<Entity>, Vec<Entity>) = children.into_iter().partition(|c| machine.is_active(c));
+
+            if parallel {
+                if !inactive.is_empty() {
+                    let _ = world.run_system_once_with(enter_below, (root, state, inactive));
+                }
+                return;
+            }
+            let initial = world.get::<InitialState>(state).map(|initial| initial.0);
+            let keep = initial.filter(|i| active.contains(i)).or(active.first().copied());
+            let mut exits = Vec::new();
+            for child in active.into_iter().filter(|&c| Some(c) != keep) {
+                exits.extend(subtree_exits(world, root, child));
+            }
+            exit_states(world, root, state, &exits);
+        });
+    }
+}
+
+/// The root of the machine in which `state` is currently active.
+fn active_machine(world: &mut World, state: Entity) -> Option<Entity> {
+    let mut q_child_of = world.query::<&StateChildOf>();
+    let root = q_child_of.query(world).root_ancestor(state);
+    world.get::<StateMachine>(root).filter(|machine| machine.is_active(&state)).map(|_| root)
+}
+
+/// Active states of the subtree at `state`, deepest first.
+fn subtree_exits(world: &mut World, root: Entity, state: Entity) -> Vec<Entity> {
+    let mut q_child_of = world.query::<&StateChildOf>();
+    let mut q_children = world.query::<&StateChildren>();
+    let mut subtree = vec![state];
+    subtree.extend(q_children.query(world).iter_descendants(state));
+    let Some(machine) = world.get::<StateMachine>(root) else { return Vec::new(); };
+    active_exit_order(&subtree, machine, &q_child_of.query(world))
+}
+
+/// Enter a state newly attached under an active parent if the parent would have entered it.
+fn enter_if_reachable(world: &mut World, state: Entity) {
+    let Some(parent) = world.get::<StateChildOf>(state).map(|child_of| child_of.0) else { return; };
+    let Some(root) = active_machine(world, parent) else { return; };
+    let Some(machine) = world.get::<StateMachine>(root) else { return; };
+    let is_parallel = world.get::<Parallel>(parent).is_some();
+    let is_initial_of_leaf = machine.is_leaf_active(&parent)
+        && world.get::<InitialState>(parent).is_some_and(|initial| initial.0 == state);
+    if is_parallel || is_initial_of_leaf {
+        let _ = world.run_system_once_with(enter_below, (root, parent, vec![state]));
+    }
+}
@@ -0,0 +1,188 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+use crate::{active::{Active, Inactive}, repair::exit_active, transitions::Paused, EnterState, ExitState, FinalState, StateChildOf, StateMachine};
+use crate::transitions::{RegisteredTransitionEvent, TransitionEvent};
+
+/// Invokes a separate machine while this state is active (SCXML `<invoke>`).
+/// `spawn` builds the chart and returns its root; `StateMachine` is inserted on the root
+/// afterwards if `spawn` didn't, so the chart starts with everything in place.
+/// When the invoked machine enters a top-level `FinalState`, this state receives `InvokeDone`.
+#[derive(Component, Clone, Copy)]
+pub struct Invoke {
+    pub spawn: fn(&mut World) -> Entity,
+    pub on_exit: InvokeExit,
+}
+
+impl Invoke {
+    pub fn new(spawn: fn(&mut World) -> Entity) -> Self {
+        Self { spawn, on_exit: InvokeExit::Despawn }
+    }
+
+    /// Keep the invoked machine when this state exits and restart it on re-entry.
+    pub fn keep_on_exit(mut self) -> Self {
+        self.on_exit = InvokeExit::Stop;
+        self
+    }
+}
+
+/// What happens to an invoked machine when the invoking state exits.
+#[derive(Reflect, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvokeExit {
+    /// Despawn the invoked chart.
+    #[default]
+    Despawn,
+    /// Stop it (exit its active states and remove `StateMachine`) and restart it on re-entry.
+    Stop,
+}
+
+/// On an invoking state: the root of the machine it invoked.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct InvokedMachine(#[entities] pub Entity);
+
+/// On an invoked machine root: the state that invoked it.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct InvokedBy(#[entities] pub Entity);
+
+/// Sent to the invoking state when its invoked machine completes. Use `EventEdge<InvokeDone>`
+/// on the invoking state to leave it then.
+#[derive(EntityEvent, Clone)]
+pub struct InvokeDone {
+    #[event_target]
+    pub target: Entity,
+    /// Root of the completed machine.
+    pub invoked: Entity,
+}
+
+impl TransitionEvent for InvokeDone {}
+impl RegisteredTransitionEvent for InvokeDone {}
+
+/// On an invoking state: forward events `E` sent to its machine to the invoked machine while active.
+#[derive(Component)]
+pub struct ForwardEvents<E: EntityEvent> {
+    _marker: PhantomData<E>,
+}
+
+impl<E: EntityEvent> Default for ForwardEvents<E> {
+    fn default() -> Self { Self { _marker: PhantomData } }
+}
+
+/// On EnterState, spawn (or restart) the invoked machine.
+pub fn start_invoke_on_enter(
+    enter_state: On<EnterState>,
+    q_invoke: Query<(&Invoke, Option<&InvokedMachine>)>,
+    mut commands: Commands,
+) {
+    let state = enter_state.target;
+    let Ok((invoke, invoked)) = q_invoke.get(state) else { return; };
+
+    if let Some(InvokedMachine(root)) = invoked {
+        commands.entity(*root).insert(StateMachine::new());
+        return;
+    }
+
+    let spawn = invoke.spawn;
+    commands.queue(move |world: &mut World| {
+        let root = spawn(world);
+        world.entity_mut(state).insert(InvokedMachine(root));
+        let mut root = world.entity_mut(root);
+        root.insert(InvokedBy(state));
+        if !root.contains::<StateMachine>() {
+            root.insert(StateMachine::new());
+        }
+    });
+}
+
+/// On ExitState, despawn or stop the invoked machine.
+pub fn stop_invoke_on_exit(
+    exit_state: On<ExitState>,
+    q_invoke: Query<(&Invoke, &InvokedMachine)>,
+    mut commands: Commands,
+) {
+    let state = exit_state.target;
+    let Ok((invoke, InvokedMachine(root))) = q_invoke.get(state) else { return; };
+    let root = *root;
+
+    match invoke.on_exit {
+        InvokeExit::Despawn => {
+            commands.entity(root).try_despawn();
+            commands.entity(state).remove::<InvokedMachine>();
+        }
+        InvokeExit::Stop => {
+            commands.queue(move |world: &mut World| stop_machine(world, root));
+        }
+    }
+}
+
+/// Exit the machine's active states deepest first, so their exit effects are undone,
+/// then remove `StateMachine`.
+fn stop_machine(world: &mut World, root: Entity) {
+    let Some(machine) = world.get::<StateMachine>(root) else { return; };
+    let mut exits: Vec<Entity> = machine.active.iter().copied().filter(|&s| s != root).collect();
+    let mut q_child_of = world.query::<&StateChildOf>();
+    let q_child_of = q_child_of.query(world);
+    exits.sort_by_key(|&s| std::cmp::Reverse(q_child_of.iter_ancestors(s).count()));
+
+    exit_active(world, root, &exits);
+    for state in exits {
+        if let Ok(mut state) = world.get_entity_mut(state) {
+            state.remove::<Active>().insert(Inactive);
+        }
+    }
+    if let Ok(mut root) = world.get_entity_mut(root) {
+        root.remove::<StateMachine>();
+    }
+}
+
+/// Despawn the invoked machine along with a despawned invoking state.
+pub fn despawn_invoked_with_state(
+    despawn: On<Despawn, InvokedMachine>,
+    q_invoked: Query<&InvokedMachine>,
+    mut commands: Commands,
+) {
+    let Ok(InvokedMachine(root)) = q_invoked.get(despawn.entity) else { return; };
+    commands.entity(*root).try_despawn();
+}
+
+/// When a machine enters a `FinalState` directly under its root, notify the state that invoked it.
+pub fn notify_invoker_on_final(
+    enter_state: On<EnterState>,
+    q_final: Query<(), With<FinalState>>,
+    q_child_of: Query<&StateChildOf>,
+    q_invoked_by: Query<&InvokedBy>,
+    mut commands: Commands,
+) {
+    let state = enter_state.target;
+    let root = enter_state.state_machine;
+    if !q_final.contains(state) { return; }
+    if q_child_of.get(state).map(|c| c.0) != Ok(root) { return; }
+    let Ok(InvokedBy(invoker)) = q_invoked_by.get(root) else { return; };
+    commands.trigger(InvokeDone { target: *invoker, invoked: root });
+}
+
+/// Forward `E` sent to a machine to the machines invoked by its active states with `ForwardEvents<E>`.
+pub fn forward_to_invoked<E: EntityEvent + Clone>(
+    event: On<E>,
+    q_child_of: Query<&StateChildOf>,
+    q_sm: Query<&StateMachine>,
+    q_paused: Query<(), With<Paused>>,
+    q_forward: Query<&InvokedMachine, With<ForwardEvents<E>>>,
+    mut commands: Commands,
+)
+where
+    for<'a> <E as Event>::Trigger<'a>: Default,
+{
+    let root = q_child_of.root_ancestor(event.event().event_target());
+    // A paused machine queues the event and forwards it when it resumes
+    if q_paused.contains(root) { return; }
+    let Ok(machine) = q_sm.get(root) else { return; };
+    for &state in machine.active.iter() {
+        let Ok(InvokedMachine(invoked)) = q_forward.get(state) else { continue; };
+        let mut forwarded = event.event().clone();
+        *forwarded.event_target_mut() = *invoked;
+        commands.trigger(forwarded);
+    }
+}
@@ -6,11 +6,20 @@ use bevy::platform::collections::HashSet;
 use crate::{active::{Active, Inactive}, guards::Guards, history::{History, HistoryState}};
 
 pub mod active;
+pub mod chart_states;
+pub mod edit;
 pub mod guards;
 pub mod history;
+pub mod invoke;
 pub mod prelude;
+pub mod repair;
 pub mod parameter;
+pub mod path;
+pub mod send;
 pub mod state_component;
+pub mod state_resource;
+pub mod state_systems;
+pub mod resettable;
 pub mod transitions;
 pub mod bevy_state;
 
@@ -33,10 +42,27 @@ impl Plugin for GearboxPlugin {
             .add_observer(transitions::always_edge_listener)
             .add_observer(transitions::start_after_on_enter)
             .add_observer(transitions::cancel_after_on_exit)
-            .add_observer(transitions::reset_on_transition_actions);
+            .add_observer(transitions::resume_paused_machine)
+            .add_observer(invoke::start_invoke_on_enter)
+            .add_observer(invoke::stop_invoke_on_exit)
+            .add_observer(invoke::notify_invoker_on_final)
+            .add_observer(transitions::start_every_on_enter)
+            .add_observer(transitions::cancel_every_on_exit)
+            .add_observer(transitions::reset_on_transition_actions)
+            .add_observer(send::tally_transitions)
+            .add_observer(repair::repair_on_state_removed)
+            .add_observer(invoke::despawn_invoked_with_state)
+            .add_observer(state_component::state_reflect_components_enter)
+            .add_observer(state_component::state_reflect_components_exit);
 
         app.register_type::<Parallel>()
+            .register_type::<FinalState>()
+            .register_type::<invoke::InvokeExit>()
+            .register_type::<invoke::InvokedMachine>()
+            .register_type::<invoke::InvokedBy>()
             .register_type::<InitialState>()
+            .register_type::<path::TargetPath>()
+            .register_type::<path::InitialStatePath>()
             .register_type::<StateMachine>()
             .register_type::<History>()
             .register_type::<HistoryState>()
@@ -48,6 +74,14 @@ impl Plugin for GearboxPlugin {
             .register_type::<ResetRegion>()
             .register_type::<TransitionActions>()
             .register_type::<transitions::After>()
+            .register_type::<transitions::Paused>()
+            .register_type::<transitions::PausedEvents>()
+            .register_type::<transitions::DeferOverflow>()
+            .register_type::<transitions::ChartRng>()
+            .register_type::<transitions::Every>()
+            .register_type::<transitions::TimerClock>()
+            .register_type::<transitions::ChartClock>()
+            .register_type::<transitions::TimeScale>()
             .register_type::<transitions::Source>()
             .register_type::<transitions::Transitions>()
             .register_type::<transitions::Target>()
@@ -56,17 +90,38 @@ impl Plugin for GearboxPlugin {
             .register_type::<transitions::NoEvent>()
             .register_type::<transitions::ResetEdge>()
             .register_type::<transitions::ResetScope>()
-            .register_type::<state_component::Reset>();
+            .register_type::<state_component::Reset>()
+            .register_type::<parameter::ParamScope>();
 
-        app.add_systems(Update, (
-            transitions::check_always_on_guards_changed,
+        app.init_resource::<repair::PendingRepairs>();
+
+        app.configure_sets(Update, (
+            parameter::ParamSystems::Sync,
+            parameter::ParamSystems::ApplyGuards,
+        ).chain().before(transitions::check_always_on_guards_changed));
+
+        app.add_systems(Update, transitions::check_always_on_guards_changed);
+        let timer_schedule = transitions::timer_schedule(app);
+        app.add_systems(timer_schedule, (
             transitions::tick_after_system,
-        ));
+            transitions::tick_every_system,
+            send::tick_delayed_sends,
+        ).in_set(transitions::AfterTimerSystems));
+
+        transitions::register_transition::<invoke::InvokeDone>(app);
 
         // Auto-register all transition events discovered via inventory
         for installer in inventory::iter::<transitions::TransitionInstaller> {
             (installer.install)(app);
         }
+
+        // Auto-register parameter and state component types discovered via inventory
+        for installer in inventory::iter::<parameter::ParamInstaller> {
+            (installer.install)(app);
+        }
+        for installer in inventory::iter::<state_component::StateComponentInstaller> {
+            (installer.install)(app);
+        }
     }
 }
 
@@ -124,6 +179,12 @@ pub struct Transition<T = ()> where T: Clone + Send + Sync + 'static {
 #[derive(EntityEvent, Reflect)]
 pub struct TransitionActions { #[event_target] pub target: Entity }
 
+/// A marker component for a final state. A machine entering a `FinalState` directly under
+/// its root is complete; if it was invoked, the invoking state receives `InvokeDone`.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct FinalState;
+
 /// A marker component for a state that has parallel (orthogonal) regions.
 /// When a state with this component is entered, the machine will simultaneously enter
 /// the initial state of each of its direct children.
@@ -450,6 +511,8 @@ fn transition_observer<T: transitions::PhasePayload>(
     transition.event().payload.on_entry(&mut commands, new_super_state, &q_children, &current_state);
     // Derive full active set from leaves
     current_state.active = compute_active_from_leaves(&current_state.active_leaves, &q_child_of);
+    // Events released by exited deferring states run once the transition has completed
+    commands.queue(transitions::replay_deferred_events(state_machine));
 }
 
 fn get_path_to_root(start_entity: Entity, q_child_of: &Query<&StateChildOf>) -> Vec<Entity> {
@@ -557,11 +620,14 @@ fn compute_active_from_leaves(
 }
 
 /// Triggers the InitializeMachine event when AbilityMachine component is added.
+/// Authored `TargetPath`s and `InitialStatePath`s are resolved first.
 fn initialize_state_machine(
     add: On<Add, StateMachine>,
+    path_authoring: path::PathAuthoring,
     mut commands: Commands,
 ) {
     let target = add.event().entity;
+    path_authoring.resolve(target, &mut commands);
     // Always attempt to initialize: root-as-leaf, parallel, or parent with InitialState
     commands.trigger(Transition { machine: target, source: target, edge: target, payload: () });
 }
@@ -1,270 +1,1346 @@
-use bevy::prelude::*;
+use bevy::{ecs::{component::Tick, lifecycle::HookContext, relationship::Relationship, system::{SystemChangeTick, SystemParam}, world::DeferredWorld}, prelude::*};
+use bevy::platform::collections::{HashMap, HashSet};
+use std::any::TypeId;
+use std::time::Duration;
 use std::marker::PhantomData;
-use crate::{guards::Guards, transitions::Source, StateChildOf};
+use crate::{guards::Guards, transitions::Source, StateChildOf, TransitionActions};
 
-/// A strongly-typed float parameter stored on an entity.
-/// The marker type `P` identifies the parameter (e.g., `Speed`).
+/// A strongly-typed parameter stored on an entity.
+/// The marker type `P` identifies the parameter (e.g., `Speed`) and `T` is the value type.
+/// Any `T` can be stored; comparison conditions require `PartialEq`/`PartialOrd`.
 #[derive(Component)]
-pub struct FloatParam<P> {
-    value: f32,
+pub struct Param<P, T> {
+    value: T,
     _marker: PhantomData<P>,
 }
 
-impl<P> Default for FloatParam<P> {
-    fn default() -> Self { Self { value: 0.0, _marker: PhantomData } }
+impl<P, T: Default> Default for Param<P, T> {
+    fn default() -> Self { Self { value: T::default(), _marker: PhantomData } }
 }
 
-impl<P> FloatParam<P> {
+impl<P, T> Param<P, T> {
     #[inline]
-    pub fn get(&self) -> f32 { self.value }
+    pub fn new(value: T) -> Self { Self { value, _marker: PhantomData } }
     #[inline]
-    pub fn set(&mut self, value: f32) { self.value = value; }
+    pub fn get(&self) -> T where T: Clone { self.value.clone() }
+    #[inline]
+    pub fn get_ref(&self) -> &T { &self.value }
+    #[inline]
+    pub fn set(&mut self, value: T) { self.value = value; }
 }
 
+/// A strongly-typed float parameter.
+pub type FloatParam<P> = Param<P, f32>;
+
 /// A strongly-typed integer parameter.
-#[derive(Component)]
-pub struct IntParam<P> {
-    value: i32,
-    _marker: PhantomData<P>,
-}
+pub type IntParam<P> = Param<P, i32>;
 
-impl<P> Default for IntParam<P> {
-    fn default() -> Self { Self { value: 0, _marker: PhantomData } }
+/// A strongly-typed boolean parameter.
+pub type BoolParam<P> = Param<P, bool>;
+
+/// Where an edge reads its parameters from. Defaults to the machine root.
+#[derive(Component, Reflect, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub enum ParamScope {
+    /// The root of the machine the edge's source belongs to.
+    #[default]
+    Root,
+    /// The edge's source state entity.
+    SourceState,
+    /// The source state or its nearest ancestor that carries the parameter.
+    /// Lets nested sub-charts override parameters locally.
+    NearestAncestor,
+    /// An arbitrary entity, e.g. maintained by `sync_param_scope_from_relationship`.
+    Entity(#[entities] Entity),
 }
 
-impl<P> IntParam<P> {
-    #[inline]
-    pub fn get(&self) -> i32 { self.value }
-    #[inline]
-    pub fn set(&mut self, value: i32) { self.value = value; }
+impl ParamScope {
+    /// Resolves the entity holding the parameter for an edge whose source is `source`.
+    /// `has_param` reports whether a given entity carries the parameter.
+    pub fn resolve(
+        &self,
+        source: Entity,
+        q_child_of: &Query<&StateChildOf>,
+        has_param: impl Fn(Entity) -> bool,
+    ) -> Option<Entity> {
+        match self {
+            ParamScope::Root => Some(q_child_of.root_ancestor(source)),
+            ParamScope::SourceState => Some(source),
+            ParamScope::NearestAncestor => std::iter::once(source)
+                .chain(q_child_of.iter_ancestors(source))
+                .find(|&e| has_param(e)),
+            ParamScope::Entity(entity) => Some(*entity),
+        }
+    }
+
+    /// Like `resolve`, walking the hierarchy through `world` (for commands and exclusive systems).
+    pub fn resolve_in_world(
+        &self,
+        source: Entity,
+        world: &World,
+        has_param: impl Fn(Entity) -> bool,
+    ) -> Option<Entity> {
+        let ancestors = std::iter::successors(Some(source), |&e| world.get::<StateChildOf>(e).map(|c| c.0));
+        match self {
+            ParamScope::Root => ancestors.last(),
+            ParamScope::SourceState => Some(source),
+            ParamScope::NearestAncestor => ancestors.into_iter().find(|&e| has_param(e)),
+            ParamScope::Entity(entity) => Some(*entity),
+        }
+    }
+
+    /// Every entity `resolve` may pick for an edge whose source is `source`.
+    pub fn candidates(&self, source: Entity, q_child_of: &Query<&StateChildOf>) -> Vec<Entity> {
+        match self {
+            ParamScope::Root => vec![q_child_of.root_ancestor(source)],
+            ParamScope::SourceState => vec![source],
+            ParamScope::NearestAncestor => std::iter::once(source)
+                .chain(q_child_of.iter_ancestors(source))
+                .collect(),
+            ParamScope::Entity(entity) => vec![*entity],
+        }
+    }
 }
 
-/// A strongly-typed boolean parameter.
+/// Edges matching filter `F`, with their optional parameter scope.
+pub type ScopedEdges<'w, 's, F> = Query<'w, 's, (Entity, &'static Source, Option<&'static ParamScope>), F>;
+
+/// Points an edge's `ParamScope` at the entity the machine root relates to through `R`
+/// (for instance the current target enemy). Add `sync_param_scope_from_relationship::<R>`
+/// to keep the scope up to date.
 #[derive(Component)]
-pub struct BoolParam<P> {
-    value: bool,
-    _marker: PhantomData<P>,
+pub struct ParamFromRelationship<R: Relationship> {
+    _marker: PhantomData<R>,
 }
 
-impl<P> Default for BoolParam<P> {
-    fn default() -> Self { Self { value: false, _marker: PhantomData } }
+impl<R: Relationship> Default for ParamFromRelationship<R> {
+    fn default() -> Self { Self { _marker: PhantomData } }
 }
 
-impl<P> BoolParam<P> {
-    #[inline]
-    pub fn get(&self) -> bool { self.value }
-    #[inline]
-    pub fn set(&mut self, value: bool) { self.value = value; }
+/// Keeps `ParamScope::Entity` on edges with `ParamFromRelationship<R>` pointing at the entity
+/// related to their machine root through `R`.
+pub fn sync_param_scope_from_relationship<R: Relationship>(
+    q_edges: ScopedEdges<With<ParamFromRelationship<R>>>,
+    q_relationship: Query<&R>,
+    q_child_of: Query<&StateChildOf>,
+    mut commands: Commands,
+) {
+    for (edge, Source(source), scope) in &q_edges {
+        let root = q_child_of.root_ancestor(*source);
+        let desired = match q_relationship.get(root) {
+            Ok(relationship) => ParamScope::Entity(relationship.get()),
+            // No related entity: read from an entity that cannot hold the param => block
+            Err(_) => ParamScope::Entity(Entity::PLACEHOLDER),
+        };
+        if scope != Some(&desired) {
+            commands.entity(edge).insert(desired);
+        }
+    }
 }
 
-/// Implement this on the marker type `P` to bind a source component `T` to a float param.
-pub trait FloatParamBinding<T: Component> {
-    fn extract(source: &T) -> f32;
+/// Reads `FloatParam<P>` in seconds for an After edge, through the edge's `ParamScope`.
+/// Used by `After::from_param`; negative values are clamped to zero.
+pub fn param_delay<P: Send + Sync + 'static>(world: &World, edge: Entity) -> Option<Duration> {
+    let Source(source) = world.get::<Source>(edge)?;
+    let holder = world
+        .get::<ParamScope>(edge)
+        .copied()
+        .unwrap_or_default()
+        .resolve_in_world(*source, world, |e| world.get::<FloatParam<P>>(e).is_some())?;
+    let seconds = world.get::<FloatParam<P>>(holder)?.get();
+    Duration::try_from_secs_f32(seconds.max(0.0)).ok()
 }
 
-/// Generic sync system: copies values from source component `T` into `FloatParam<P>`.
-pub fn sync_float_param<T, P>(
-    mut q: Query<(&T, &mut FloatParam<P>)>,
+/// Implement this on the marker type `P` to bind a source component `S` to a `Param<P, T>`.
+pub trait ParamBinding<S: Component, T> {
+    fn extract(source: &S) -> T;
+}
+
+/// Generic sync system: copies values from source component `S` into `Param<P, T>`.
+/// The parameter is only written when the value differs, so `Changed` stays meaningful.
+pub fn sync_param<S, P, T>(
+    mut q: Query<(&S, &mut Param<P, T>)>,
 )
 where
-    T: Component,
-    P: FloatParamBinding<T> + Send + Sync + 'static,
+    S: Component,
+    P: ParamBinding<S, T> + Send + Sync + 'static,
+    T: PartialEq + Send + Sync + 'static,
 {
     for (src, mut param) in &mut q {
-        param.set(P::extract(src));
+        // Only write on change so change-driven guard application stays idle
+        let value = P::extract(src);
+        if *param.get_ref() != value { param.set(value); }
+    }
+}
+
+/// Shorthand for `ParamBinding<T, f32>`: implement it to bind `T` to a `FloatParam<P>`.
+pub trait FloatParamBinding<T: Component> {
+    fn extract(source: &T) -> f32;
+}
+
+/// Shorthand for `ParamBinding<T, i32>`: implement it to bind `T` to an `IntParam<P>`.
+pub trait IntParamBinding<T: Component> {
+    fn extract(source: &T) -> i32;
+}
+
+/// Shorthand for `ParamBinding<T, bool>`: implement it to bind `T` to a `BoolParam<P>`.
+pub trait BoolParamBinding<T: Component> {
+    fn extract(source: &T) -> bool;
+}
+
+macro_rules! impl_typed_param_binding {
+    ($binding:ident, $value:ty) => {
+        impl<T: Component, P: $binding<T>> ParamBinding<T, $value> for P {
+            fn extract(source: &T) -> $value { <P as $binding<T>>::extract(source) }
+        }
+    };
+}
+
+impl_typed_param_binding!(FloatParamBinding, f32);
+impl_typed_param_binding!(IntParamBinding, i32);
+impl_typed_param_binding!(BoolParamBinding, bool);
+
+/// `sync_param::<T, P, f32>` for `FloatParamBinding`s.
+pub fn sync_float_param<T: Component, P: FloatParamBinding<T> + Send + Sync + 'static>(q: Query<(&T, &mut FloatParam<P>)>) {
+    sync_param::<T, P, f32>(q);
+}
+
+/// `sync_param::<T, P, i32>` for `IntParamBinding`s.
+pub fn sync_int_param<T: Component, P: IntParamBinding<T> + Send + Sync + 'static>(q: Query<(&T, &mut IntParam<P>)>) {
+    sync_param::<T, P, i32>(q);
+}
+
+/// `sync_param::<T, P, bool>` for `BoolParamBinding`s.
+pub fn sync_bool_param<T: Component, P: BoolParamBinding<T> + Send + Sync + 'static>(q: Query<(&T, &mut BoolParam<P>)>) {
+    sync_param::<T, P, bool>(q);
+}
+
+/// An edge condition over a single `Param<Self::Param, Self::Value>`.
+/// Edges carrying a condition component are blocked by a guard while the condition fails.
+pub trait ParamCondition: Component {
+    /// Marker type identifying the parameter.
+    type Param: Send + Sync + 'static;
+    /// Value type stored in the parameter.
+    type Value: Send + Sync + 'static;
+
+    fn is_satisfied(&self, value: &Self::Value) -> bool;
+
+    /// Stateful evaluation given whether the condition was satisfied at the previous
+    /// evaluation of this edge (`None` on the first one). Override for hysteresis.
+    fn evaluate(&self, value: &Self::Value, _previously_satisfied: Option<bool>) -> bool {
+        self.is_satisfied(value)
+    }
+
+    /// How long a new result must persist before the guard flips. Zero flips immediately.
+    fn min_hold(&self) -> Duration { Duration::ZERO }
+
+    /// Guard key inserted into `Guards` while the condition fails.
+    fn guard_key() -> String {
+        format!("param::<{}>", std::any::type_name::<Self>())
+    }
+}
+
+/// Equality condition: passes when the parameter equals `expected`.
+#[derive(Component, Clone)]
+pub struct Equals<P, T> {
+    pub expected: T,
+    _marker: PhantomData<P>,
+}
+
+impl<P, T> Equals<P, T> {
+    pub fn new(expected: T) -> Self { Self { expected, _marker: PhantomData } }
+}
+
+impl<P: Send + Sync + 'static, T: PartialEq + Send + Sync + 'static> ParamCondition for Equals<P, T> {
+    type Param = P;
+    type Value = T;
+    fn is_satisfied(&self, value: &T) -> bool { *value == self.expected }
+}
+
+/// Inequality condition: passes when the parameter differs from `unexpected`.
+#[derive(Component, Clone)]
+pub struct NotEquals<P, T> {
+    pub unexpected: T,
+    _marker: PhantomData<P>,
+}
+
+impl<P, T> NotEquals<P, T> {
+    pub fn new(unexpected: T) -> Self { Self { unexpected, _marker: PhantomData } }
+}
+
+impl<P: Send + Sync + 'static, T: PartialEq + Send + Sync + 'static> ParamCondition for NotEquals<P, T> {
+    type Param = P;
+    type Value = T;
+    fn is_satisfied(&self, value: &T) -> bool { *value != self.unexpected }
+}
+
+/// Inclusive range condition: passes when `min <= value <= max`.
+#[derive(Component, Clone)]
+pub struct InRange<P, T> {
+    pub min: T,
+    pub max: T,
+    _marker: PhantomData<P>,
+}
+
+impl<P, T> InRange<P, T> {
+    pub fn new(min: T, max: T) -> Self { Self { min, max, _marker: PhantomData } }
+}
+
+impl<P: Send + Sync + 'static, T: PartialOrd + Send + Sync + 'static> ParamCondition for InRange<P, T> {
+    type Param = P;
+    type Value = T;
+    fn is_satisfied(&self, value: &T) -> bool { *value >= self.min && *value <= self.max }
+}
+
+/// Strict lower bound condition: passes when `value > threshold`.
+#[derive(Component, Clone)]
+pub struct GreaterThan<P, T> {
+    pub threshold: T,
+    _marker: PhantomData<P>,
+}
+
+impl<P, T> GreaterThan<P, T> {
+    pub fn new(threshold: T) -> Self { Self { threshold, _marker: PhantomData } }
+}
+
+impl<P: Send + Sync + 'static, T: PartialOrd + Send + Sync + 'static> ParamCondition for GreaterThan<P, T> {
+    type Param = P;
+    type Value = T;
+    fn is_satisfied(&self, value: &T) -> bool { *value > self.threshold }
+}
+
+/// Strict upper bound condition: passes when `value < threshold`.
+#[derive(Component, Clone)]
+pub struct LessThan<P, T> {
+    pub threshold: T,
+    _marker: PhantomData<P>,
+}
+
+impl<P, T> LessThan<P, T> {
+    pub fn new(threshold: T) -> Self { Self { threshold, _marker: PhantomData } }
+}
+
+impl<P: Send + Sync + 'static, T: PartialOrd + Send + Sync + 'static> ParamCondition for LessThan<P, T> {
+    type Param = P;
+    type Value = T;
+    fn is_satisfied(&self, value: &T) -> bool { *value < self.threshold }
+}
+
+/// Membership condition: passes when the parameter equals any of `values`.
+#[derive(Component, Clone)]
+pub struct OneOf<P, T> {
+    pub values: Vec<T>,
+    _marker: PhantomData<P>,
+}
+
+impl<P, T> OneOf<P, T> {
+    pub fn new(values: impl IntoIterator<Item = T>) -> Self {
+        Self { values: values.into_iter().collect(), _marker: PhantomData }
     }
 }
 
-/// Float range condition with optional hysteresis margin.
+impl<P: Send + Sync + 'static, T: PartialEq + Send + Sync + 'static> ParamCondition for OneOf<P, T> {
+    type Param = P;
+    type Value = T;
+    fn is_satisfied(&self, value: &T) -> bool { self.values.contains(value) }
+}
+
+/// Float range condition with hysteresis.
+/// Once satisfied it stays satisfied until the value leaves `[min, max]` by more than
+/// `hysteresis`; once unsatisfied the value must come `hysteresis` inside the range to pass.
+/// `new` clamps `hysteresis` to `[0, (max - min) / 2]` so the range can always be re-entered.
+/// With `min_hold`, a new result must persist that long before the guard flips.
 #[derive(Component, Clone, Copy)]
 pub struct FloatInRange<P> {
     pub min: f32,
     pub max: f32,
     pub hysteresis: f32,
+    pub min_hold: Duration,
     _marker: PhantomData<P>,
 }
 
 impl<P> FloatInRange<P> {
     pub fn new(min: f32, max: f32, hysteresis: f32) -> Self {
-        Self { min, max, hysteresis, _marker: PhantomData }
+        let hysteresis = hysteresis.max(0.0).min(((max - min) / 2.0).max(0.0));
+        Self { min, max, hysteresis, min_hold: Duration::ZERO, _marker: PhantomData }
+    }
+
+    pub fn with_min_hold(mut self, min_hold: Duration) -> Self {
+        self.min_hold = min_hold;
+        self
     }
 }
 
-fn guard_key_for_float<P>() -> String { format!("float-in-range::<{}>", std::any::type_name::<P>()) }
+impl<P: Send + Sync + 'static> ParamCondition for FloatInRange<P> {
+    type Param = P;
+    type Value = f32;
 
-/// Update Guards on edges with FloatInRange<P> based on the current FloatParam<P> value.
-/// Works seamlessly with AlwaysEdge and EventEdge since both consult Guards.
-pub fn apply_float_param_guards<P: Send + Sync + 'static>(
-    q_edges: Query<(Entity, &Source, &FloatInRange<P>)>,
-    q_params: Query<&FloatParam<P>>,
-    q_child_of: Query<&StateChildOf>,
-    mut q_guards: Query<&mut Guards>,
-    mut commands: Commands,
-) {
-    let key = guard_key_for_float::<P>();
-    for (edge, Source(source), range) in &q_edges {
-        let root = q_child_of.root_ancestor(*source);
-        // Determine desired presence of this guard without mutating existing component
-        let desired_blocked = match q_params.get(root) {
-            Ok(param) => {
-                let v = param.get();
-                !(v + range.hysteresis >= range.min && v - range.hysteresis <= range.max)
-            }
-            Err(_) => true, // missing param => block
-        };
+    fn is_satisfied(&self, value: &f32) -> bool {
+        *value >= self.min && *value <= self.max
+    }
 
-        // Read current presence (if any) without triggering change detection
-        let current_has = q_guards
-            .get(edge)
-            .ok()
-            .map(|g| g.has_guard(key.as_str()))
-            .unwrap_or(false);
-
-        // Only mutate when the membership actually changes
-        if desired_blocked != current_has {
-            if let Ok(mut g) = q_guards.get_mut(edge) {
-                if desired_blocked { g.add_guard(key.as_str()); }
-                else { g.remove_guard(key.as_str()); }
-            } else if desired_blocked {
-                // Insert only when we actually need to block
-                commands.entity(edge).insert(Guards::init([key.as_str()]));
-            }
+    fn evaluate(&self, value: &f32, previously_satisfied: Option<bool>) -> bool {
+        let v = *value;
+        match previously_satisfied {
+            Some(true) => v >= self.min - self.hysteresis && v <= self.max + self.hysteresis,
+            Some(false) => v >= self.min + self.hysteresis && v <= self.max - self.hysteresis,
+            None => self.is_satisfied(value),
         }
     }
-}
 
-/// Implement this on the marker type `P` to bind a source component `T` to an int param.
-pub trait IntParamBinding<T: Component> {
-    fn extract(source: &T) -> i32;
-}
+    fn min_hold(&self) -> Duration { self.min_hold }
 
-/// Generic sync system: copies values from source component `T` into `IntParam<P>`.
-pub fn sync_int_param<T, P>(
-    mut q: Query<(&T, &mut IntParam<P>)>,
-)
-where
-    T: Component,
-    P: IntParamBinding<T> + Send + Sync + 'static,
-{
-    for (src, mut param) in &mut q {
-        param.set(P::extract(src));
-    }
+    fn guard_key() -> String { format!("float-in-range::<{}>", std::any::type_name::<P>()) }
 }
 
-/// Integer range condition with optional hysteresis margin.
+/// Integer range condition with hysteresis, see `FloatInRange`. `new` clamps `hysteresis`
+/// to `[0, (max - min) / 2]` likewise.
 #[derive(Component, Clone, Copy)]
 pub struct IntInRange<P> {
     pub min: i32,
     pub max: i32,
     pub hysteresis: i32,
+    pub min_hold: Duration,
     _marker: PhantomData<P>,
 }
 
 impl<P> IntInRange<P> {
     pub fn new(min: i32, max: i32, hysteresis: i32) -> Self {
-        Self { min, max, hysteresis, _marker: PhantomData }
+        let half_width = ((max as i64 - min as i64) / 2).max(0);
+        let hysteresis = (hysteresis as i64).clamp(0, half_width) as i32;
+        Self { min, max, hysteresis, min_hold: Duration::ZERO, _marker: PhantomData }
+    }
+
+    pub fn with_min_hold(mut self, min_hold: Duration) -> Self {
+        self.min_hold = min_hold;
+        self
     }
 }
 
-fn guard_key_for_int<P>() -> String { format!("int-in-range::<{}>", std::any::type_name::<P>()) }
+impl<P: Send + Sync + 'static> ParamCondition for IntInRange<P> {
+    type Param = P;
+    type Value = i32;
 
-/// Update Guards on edges with IntInRange<P> based on the current IntParam<P> value.
-pub fn apply_int_param_guards<P: Send + Sync + 'static>(
-    q_edges: Query<(Entity, &Source, &IntInRange<P>)>,
-    q_params: Query<&IntParam<P>>,
-    q_child_of: Query<&StateChildOf>,
-    mut q_guards: Query<&mut Guards>,
+    fn is_satisfied(&self, value: &i32) -> bool {
+        *value >= self.min && *value <= self.max
+    }
+
+    fn evaluate(&self, value: &i32, previously_satisfied: Option<bool>) -> bool {
+        // widen to avoid overflow at the i32 bounds
+        let (v, min, max, h) = (*value as i64, self.min as i64, self.max as i64, self.hysteresis as i64);
+        match previously_satisfied {
+            Some(true) => v >= min - h && v <= max + h,
+            Some(false) => v >= min + h && v <= max - h,
+            None => self.is_satisfied(value),
+        }
+    }
+
+    fn min_hold(&self) -> Duration { self.min_hold }
+
+    fn guard_key() -> String { format!("int-in-range::<{}>", std::any::type_name::<P>()) }
+}
+
+/// Boolean equality condition.
+pub type BoolEquals<P> = Equals<P, bool>;
+
+/// Edges carrying condition `C`, with their optional parameter scope.
+pub type ConditionEdges<'w, 's, C> = Query<'w, 's, (Entity, &'static Source, &'static C, Option<&'static ParamScope>)>;
+
+/// Per-entity index of the edges that may read `Param<P, T>` from this entity.
+/// Maintained by `apply_param_guards` and `apply_compare_guards` so parameter changes only
+/// re-evaluate dependent edges. Each guard system holds its own reference to an edge.
+#[derive(Component)]
+pub struct ParamDependents<P, T> {
+    edges: HashMap<Entity, usize>,
+    _marker: PhantomData<(P, T)>,
+}
+
+impl<P, T> Default for ParamDependents<P, T> {
+    fn default() -> Self { Self { edges: HashMap::new(), _marker: PhantomData } }
+}
+
+impl<P, T> ParamDependents<P, T> {
+    #[inline]
+    pub fn edges(&self) -> impl Iterator<Item = Entity> + '_ { self.edges.keys().copied() }
+}
+
+/// Adds a reference to `edge` to the `ParamDependents<P, T>` of every entity in `holders`.
+fn index_dependents<P: Send + Sync + 'static, T: Send + Sync + 'static>(
+    edge: Entity,
+    holders: &[Entity],
+    commands: &mut Commands,
+) {
+    for &holder in holders {
+        let Ok(mut holder) = commands.get_entity(holder) else { continue; };
+        holder
+            .entry::<ParamDependents<P, T>>()
+            .or_default()
+            .and_modify(move |mut dependents| { *dependents.edges.entry(edge).or_default() += 1; });
+    }
+}
+
+/// Drops a reference to `edge` from the `ParamDependents<P, T>` of every entity in `holders`.
+fn unindex_dependents<P: Send + Sync + 'static, T: Send + Sync + 'static>(
+    edge: Entity,
+    holders: &[Entity],
+    commands: &mut Commands,
+) {
+    for &holder in holders {
+        let Ok(mut holder) = commands.get_entity(holder) else { continue; };
+        holder
+            .entry::<ParamDependents<P, T>>()
+            .and_modify(move |mut dependents| {
+                let Some(count) = dependents.edges.get_mut(&edge) else { return; };
+                *count -= 1;
+                if *count == 0 { dependents.edges.remove(&edge); }
+            });
+    }
+}
+
+/// The parameter read by condition `C`.
+pub type ConditionParam<C> = Param<<C as ParamCondition>::Param, <C as ParamCondition>::Value>;
+
+/// The dependents index for the parameter read by condition `C`.
+pub type ConditionDependents<C> = ParamDependents<<C as ParamCondition>::Param, <C as ParamCondition>::Value>;
+
+/// Filter for edges whose condition `C`, source or scope changed since the last run.
+pub type ReconfiguredEdge<C> = (With<C>, Or<(Changed<C>, Changed<Source>, Changed<ParamScope>)>);
+
+/// Detects edges with condition `C` whose parameter holders may have changed: new or
+/// reconfigured edges, edges whose scope was removed, and edges whose source moved.
+#[derive(SystemParam)]
+pub struct EdgeReconfigurations<'w, 's, C: Component> {
+    changed_edges: Query<'w, 's, Entity, ReconfiguredEdge<C>>,
+    moved_states: Query<'w, 's, Entity, Changed<StateChildOf>>,
+    removed_scopes: RemovedComponents<'w, 's, ParamScope>,
+    removed_parents: RemovedComponents<'w, 's, StateChildOf>,
+}
+
+impl<C: Component> EdgeReconfigurations<'_, '_, C> {
+    /// Edges to re-index since the last run.
+    pub fn collect(&mut self, edges: &ConditionEdges<C>, child_of: &Query<&StateChildOf>) -> HashSet<Entity> {
+        let mut reindex: HashSet<Entity> = self.changed_edges.iter().collect();
+        reindex.extend(self.removed_scopes.read().filter(|&e| edges.contains(e)));
+        let moved: HashSet<Entity> = self.moved_states.iter()
+            .chain(self.removed_parents.read())
+            .collect();
+        if !moved.is_empty() {
+            for (edge, Source(source), _, _) in edges {
+                let mut lineage = std::iter::once(*source).chain(child_of.iter_ancestors(*source));
+                if lineage.any(|e| moved.contains(&e)) {
+                    reindex.insert(edge);
+                }
+            }
+        }
+        reindex
+    }
+}
+
+/// Per-edge memory of condition `C`: the current result and a pending flip waiting
+/// out `ParamCondition::min_hold`. Managed by `apply_param_guards`.
+#[derive(Component)]
+pub struct ConditionState<C> {
+    pub satisfied: bool,
+    /// Result waiting to be applied and the elapsed time at which it was first observed.
+    pub pending: Option<(bool, Duration)>,
+    _marker: PhantomData<C>,
+}
+
+impl<C> ConditionState<C> {
+    fn new(satisfied: bool, pending: Option<(bool, Duration)>) -> Self {
+        Self { satisfied, pending, _marker: PhantomData }
+    }
+}
+
+/// Queries used by `apply_param_guards` for condition `C`.
+#[derive(SystemParam)]
+pub struct ParamGuardQueries<'w, 's, C: ParamCondition> {
+    edges: ConditionEdges<'w, 's, C>,
+    reconfigured: EdgeReconfigurations<'w, 's, C>,
+    changed_params: Query<'w, 's, &'static ConditionDependents<C>, Changed<ConditionParam<C>>>,
+    params: Query<'w, 's, &'static ConditionParam<C>>,
+    child_of: Query<'w, 's, &'static StateChildOf>,
+    guards: Query<'w, 's, &'static mut Guards>,
+    states: Query<'w, 's, &'static mut ConditionState<C>>,
+    removed_params: RemovedComponents<'w, 's, ConditionParam<C>>,
+    removed_conditions: RemovedComponents<'w, 's, C>,
+    time: Res<'w, Time>,
+    /// Edges with a pending flip; re-evaluated every run until it settles.
+    holding: Local<'s, HashSet<Entity>>,
+    /// The entities each edge is currently indexed at, so stale entries can be pruned.
+    indexed: Local<'s, HashMap<Entity, Vec<Entity>>>,
+}
+
+/// Update Guards on edges with condition `C` based on the current `Param<C::Param, C::Value>`
+/// read from the edge's `ParamScope` (the machine root by default).
+/// Works seamlessly with AlwaysEdge and EventEdge since both consult Guards.
+///
+/// Change-driven: only edges whose condition, source, scope or source hierarchy changed, and
+/// edges indexed in `ParamDependents` of an entity whose parameter changed or was removed, are
+/// re-evaluated. Edges that lose `C` have their guard lifted and are dropped from the index.
+pub fn apply_param_guards<C: ParamCondition>(
+    mut queries: ParamGuardQueries<C>,
     mut commands: Commands,
-){
-    let key = guard_key_for_int::<P>();
-    for (edge, Source(source), range) in &q_edges {
-        let root = q_child_of.root_ancestor(*source);
-        let desired_blocked = match q_params.get(root) {
-            Ok(param) => {
-                let v = param.get();
-                // inclusive range with hysteresis margin
-                !((v + range.hysteresis) as i64 >= range.min as i64 && (v - range.hysteresis) as i64 <= range.max as i64)
+) {
+    let mut dirty: HashSet<Entity> = HashSet::new();
+    let key = C::guard_key();
+
+    // Edges that lost their condition (or were despawned): lift the guard and unindex them
+    for edge in queries.removed_conditions.read() {
+        queries.holding.remove(&edge);
+        let holders = queries.indexed.remove(&edge).unwrap_or_default();
+        unindex_dependents::<C::Param, C::Value>(edge, &holders, &mut commands);
+        if queries.edges.contains(edge) { continue; }
+        set_guard_blocked(edge, key.as_str(), false, &mut queries.guards, &mut commands);
+        if let Ok(mut edge) = commands.get_entity(edge) {
+            edge.remove::<ConditionState<C>>();
+        }
+    }
+
+    // New or reconfigured edges, and edges whose scope was removed or whose source moved:
+    // evaluate them and index them at every entity they may read from
+    for edge in queries.reconfigured.collect(&queries.edges, &queries.child_of) {
+        dirty.insert(edge);
+        let holders = queries.indexed.remove(&edge).unwrap_or_default();
+        unindex_dependents::<C::Param, C::Value>(edge, &holders, &mut commands);
+        let Ok((_, Source(source), _, scope)) = queries.edges.get(edge) else { continue; };
+        let holders = scope.copied().unwrap_or_default().candidates(*source, &queries.child_of);
+        index_dependents::<C::Param, C::Value>(edge, &holders, &mut commands);
+        queries.indexed.insert(edge, holders);
+    }
+
+    // Changed parameters: evaluate their dependent edges
+    for dependents in &queries.changed_params {
+        dirty.extend(dependents.edges());
+    }
+
+    // Removed parameters: their dependents fall back to another holder or block
+    let removed: HashSet<Entity> = queries.removed_params.read().collect();
+    if !removed.is_empty() {
+        dirty.extend(queries.indexed.iter()
+            .filter(|(_, holders)| holders.iter().any(|h| removed.contains(h)))
+            .map(|(&edge, _)| edge));
+    }
+
+    // Edges waiting out a minimum hold
+    dirty.extend(queries.holding.drain());
+
+    let now = queries.time.elapsed();
+    for edge in dirty {
+        let Ok((_, Source(source), condition, scope)) = queries.edges.get(edge) else { continue; };
+        let holder = scope
+            .copied()
+            .unwrap_or_default()
+            .resolve(*source, &queries.child_of, |e| queries.params.contains(e));
+        let state = queries.states.get(edge).ok();
+        let previous = state.map(|s| s.satisfied);
+        let previous_pending = state.and_then(|s| s.pending);
+
+        let (satisfied, pending) = match holder.and_then(|e| queries.params.get(e).ok()) {
+            Some(param) => {
+                let result = condition.evaluate(param.get_ref(), previous);
+                match previous {
+                    Some(previous) if result != previous && !condition.min_hold().is_zero() => {
+                        // Keep the old result until the new one has persisted for min_hold
+                        let since = match previous_pending {
+                            Some((pending, since)) if pending == result => since,
+                            _ => now,
+                        };
+                        if now.saturating_sub(since) >= condition.min_hold() {
+                            (result, None)
+                        } else {
+                            queries.holding.insert(edge);
+                            (previous, Some((result, since)))
+                        }
+                    }
+                    _ => (result, None),
+                }
             }
-            Err(_) => true,
+            None => (false, None), // missing param => block
         };
 
-        let current_has = q_guards
-            .get(edge)
-            .ok()
-            .map(|g| g.has_guard(key.as_str()))
-            .unwrap_or(false);
-
-        if desired_blocked != current_has {
-            if let Ok(mut g) = q_guards.get_mut(edge) {
-                if desired_blocked { g.add_guard(key.as_str()); }
-                else { g.remove_guard(key.as_str()); }
-            } else if desired_blocked {
-                commands.entity(edge).insert(Guards::init([key.as_str()]));
+        if let Ok(mut state) = queries.states.get_mut(edge) {
+            if state.satisfied != satisfied || state.pending != pending {
+                state.satisfied = satisfied;
+                state.pending = pending;
             }
+        } else {
+            commands.entity(edge).insert(ConditionState::<C>::new(satisfied, pending));
         }
+
+        set_guard_blocked(edge, key.as_str(), !satisfied, &mut queries.guards, &mut commands);
     }
 }
 
-/// Implement this on the marker type `P` to bind a source component `T` to a bool param.
-pub trait BoolParamBinding<T: Component> {
-    fn extract(source: &T) -> bool;
+/// Adds or removes guard `key` on `edge`, only touching `Guards` when membership changes.
+fn set_guard_blocked(
+    edge: Entity,
+    key: &str,
+    desired_blocked: bool,
+    q_guards: &mut Query<&mut Guards>,
+    commands: &mut Commands,
+) {
+    // Read current presence (if any) without triggering change detection
+    let current_has = q_guards
+        .get(edge)
+        .ok()
+        .map(|g| g.has_guard(key))
+        .unwrap_or(false);
+
+    // Only mutate when the membership actually changes
+    if desired_blocked != current_has {
+        if let Ok(mut g) = q_guards.get_mut(edge) {
+            if desired_blocked { g.add_guard(key); }
+            else { g.remove_guard(key); }
+        } else if desired_blocked {
+            // Insert only when we actually need to block
+            commands.entity(edge).insert(Guards::init([key]));
+        }
+    }
 }
 
-/// Generic sync system: copies values from source component `T` into `BoolParam<P>`.
-pub fn sync_bool_param<T, P>(
-    mut q: Query<(&T, &mut BoolParam<P>)>,
-)
+/// Update Guards on edges with FloatInRange<P> based on the current FloatParam<P> value.
+pub fn apply_float_param_guards<P: Send + Sync + 'static>(
+    queries: ParamGuardQueries<FloatInRange<P>>,
+    commands: Commands,
+) {
+    apply_param_guards::<FloatInRange<P>>(queries, commands);
+}
+
+/// Update Guards on edges with IntInRange<P> based on the current IntParam<P> value.
+pub fn apply_int_param_guards<P: Send + Sync + 'static>(
+    queries: ParamGuardQueries<IntInRange<P>>,
+    commands: Commands,
+) {
+    apply_param_guards::<IntInRange<P>>(queries, commands);
+}
+
+/// Update Guards on edges with BoolEquals<P> based on the current BoolParam<P> value.
+pub fn apply_bool_param_guards<P: Send + Sync + 'static>(
+    queries: ParamGuardQueries<BoolEquals<P>>,
+    commands: Commands,
+) {
+    apply_param_guards::<BoolEquals<P>>(queries, commands);
+}
+
+/// Context handed to `Operand::value`: the world and where the edge reads parameters from.
+pub struct OperandContext<'a, 'w, 's> {
+    pub world: &'a World,
+    /// The edge's source state.
+    pub source: Entity,
+    /// The edge's `ParamScope` (the machine root by default).
+    pub scope: ParamScope,
+    pub child_of: &'a Query<'w, 's, &'static StateChildOf>,
+}
+
+impl OperandContext<'_, '_, '_> {
+    /// Resolves the entity to read from; `has_param` reports whether an entity carries the value.
+    pub fn holder(&self, has_param: impl Fn(Entity) -> bool) -> Option<Entity> {
+        self.scope.resolve(self.source, self.child_of, has_param)
+    }
+}
+
+/// What changed for `Compare` operands since the guard system last ran.
+#[derive(Default, Debug)]
+pub struct OperandChanges {
+    /// Edges indexed at an entity whose parameter changed or was removed.
+    pub edges: HashSet<Entity>,
+    /// Despawned entities that held a parameter; edges reading from them are re-evaluated.
+    pub despawned: HashSet<Entity>,
+    /// A value read outside the parameter index (e.g. a resource) changed: re-evaluate every edge.
+    pub all: bool,
+}
+
+/// A numeric value compared by `Compare`. Parameters, resources, constants and
+/// derived expressions (`Sum`, `Difference`, `Product`, `Ratio`, `Abs`) all implement it.
+pub trait Operand: Send + Sync + 'static {
+    /// Returns `None` when the value is unavailable (missing parameter or resource,
+    /// division by zero); the comparison then fails.
+    fn value(&self, context: &OperandContext) -> Option<f64>;
+
+    /// Indexes `edge` in the `ParamDependents` of the `holders` it may read parameters from.
+    fn index(_edge: Entity, _holders: &[Entity], _commands: &mut Commands) where Self: Sized {}
+
+    /// Undoes `index`.
+    fn unindex(_edge: Entity, _holders: &[Entity], _commands: &mut Commands) where Self: Sized {}
+
+    /// Records what this operand reads that changed between `last_run` and `this_run`.
+    fn collect_changes(_world: &World, _last_run: Tick, _this_run: Tick, _changes: &mut OperandChanges) where Self: Sized {}
+}
+
+/// Reads `Param<P, T>` from the edge's `ParamScope`.
+pub struct ParamValue<P, T = f32> {
+    _marker: PhantomData<(P, T)>,
+}
+
+impl<P, T> ParamValue<P, T> {
+    #[inline]
+    pub fn new() -> Self { Self { _marker: PhantomData } }
+}
+
+impl<P, T> Default for ParamValue<P, T> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<P, T> Operand for ParamValue<P, T>
 where
-    T: Component,
-    P: BoolParamBinding<T> + Send + Sync + 'static,
+    P: Send + Sync + 'static,
+    T: Copy + Into<f64> + Send + Sync + 'static,
 {
-    for (src, mut param) in &mut q {
-        param.set(P::extract(src));
+    fn value(&self, context: &OperandContext) -> Option<f64> {
+        let holder = context.holder(|e| context.world.get::<Param<P, T>>(e).is_some())?;
+        context.world.get::<Param<P, T>>(holder).map(|param| (*param.get_ref()).into())
+    }
+
+    fn index(edge: Entity, holders: &[Entity], commands: &mut Commands) {
+        index_dependents::<P, T>(edge, holders, commands);
+    }
+
+    fn unindex(edge: Entity, holders: &[Entity], commands: &mut Commands) {
+        unindex_dependents::<P, T>(edge, holders, commands);
+    }
+
+    fn collect_changes(world: &World, last_run: Tick, this_run: Tick, changes: &mut OperandChanges) {
+        if let Some(q_params) = world.try_query::<(Ref<Param<P, T>>, &ParamDependents<P, T>)>() {
+            for (param, dependents) in q_params.iter_manual(world) {
+                if param.last_changed().is_newer_than(last_run, this_run) {
+                    changes.edges.extend(dependents.edges());
+                }
+            }
+        }
+        for holder in world.removed::<Param<P, T>>() {
+            match world.get::<ParamDependents<P, T>>(holder) {
+                Some(dependents) => changes.edges.extend(dependents.edges()),
+                None => { changes.despawned.insert(holder); }
+            }
+        }
     }
 }
 
-/// Boolean equality condition.
+/// Reads a value out of resource `R`, e.g. `ResourceValue::new(|s: &Settings| s.flee_ratio as f64)`.
+pub struct ResourceValue<R: Resource> {
+    read: fn(&R) -> f64,
+}
+
+impl<R: Resource> ResourceValue<R> {
+    #[inline]
+    pub fn new(read: fn(&R) -> f64) -> Self { Self { read } }
+}
+
+impl<R: Resource> Operand for ResourceValue<R> {
+    fn value(&self, context: &OperandContext) -> Option<f64> {
+        context.world.get_resource::<R>().map(self.read)
+    }
+
+    fn collect_changes(world: &World, last_run: Tick, this_run: Tick, changes: &mut OperandChanges) {
+        // A missing resource may be re-added at any time, so keep checking while it's absent
+        changes.all |= world.get_resource_change_ticks::<R>()
+            .is_none_or(|ticks| ticks.is_changed(last_run, this_run));
+    }
+}
+
+/// A constant operand.
+#[derive(Clone, Copy, Debug)]
+pub struct Const(pub f64);
+
+impl Operand for Const {
+    fn value(&self, _context: &OperandContext) -> Option<f64> { Some(self.0) }
+}
+
+/// Change tracking of an expression over operands `A` and `B`: the union of both.
+macro_rules! binary_operand_tracking {
+    () => {
+        fn index(edge: Entity, holders: &[Entity], commands: &mut Commands) {
+            A::index(edge, holders, commands);
+            B::index(edge, holders, commands);
+        }
+
+        fn unindex(edge: Entity, holders: &[Entity], commands: &mut Commands) {
+            A::unindex(edge, holders, commands);
+            B::unindex(edge, holders, commands);
+        }
+
+        fn collect_changes(world: &World, last_run: Tick, this_run: Tick, changes: &mut OperandChanges) {
+            A::collect_changes(world, last_run, this_run, changes);
+            B::collect_changes(world, last_run, this_run, changes);
+        }
+    };
+}
+
+/// `A + B`.
+pub struct Sum<A, B>(pub A, pub B);
+
+impl<A: Operand, B: Operand> Operand for Sum<A, B> {
+    fn value(&self, context: &OperandContext) -> Option<f64> {
+        Some(self.0.value(context)? + self.1.value(context)?)
+    }
+
+    binary_operand_tracking!();
+}
+
+/// `A - B`.
+pub struct Difference<A, B>(pub A, pub B);
+
+impl<A: Operand, B: Operand> Operand for Difference<A, B> {
+    fn value(&self, context: &OperandContext) -> Option<f64> {
+        Some(self.0.value(context)? - self.1.value(context)?)
+    }
+
+    binary_operand_tracking!();
+}
+
+/// `A * B`.
+pub struct Product<A, B>(pub A, pub B);
+
+impl<A: Operand, B: Operand> Operand for Product<A, B> {
+    fn value(&self, context: &OperandContext) -> Option<f64> {
+        Some(self.0.value(context)? * self.1.value(context)?)
+    }
+
+    binary_operand_tracking!();
+}
+
+/// `A / B`; unavailable when `B` is zero.
+pub struct Ratio<A, B>(pub A, pub B);
+
+impl<A: Operand, B: Operand> Operand for Ratio<A, B> {
+    fn value(&self, context: &OperandContext) -> Option<f64> {
+        let denominator = self.1.value(context)?;
+        if denominator == 0.0 { return None; }
+        Some(self.0.value(context)? / denominator)
+    }
+
+    binary_operand_tracking!();
+}
+
+/// `|A|`.
+pub struct Abs<A>(pub A);
+
+impl<A: Operand> Operand for Abs<A> {
+    fn value(&self, context: &OperandContext) -> Option<f64> {
+        self.0.value(context).map(f64::abs)
+    }
+
+    fn index(edge: Entity, holders: &[Entity], commands: &mut Commands) { A::index(edge, holders, commands); }
+
+    fn unindex(edge: Entity, holders: &[Entity], commands: &mut Commands) { A::unindex(edge, holders, commands); }
+
+    fn collect_changes(world: &World, last_run: Tick, this_run: Tick, changes: &mut OperandChanges) {
+        A::collect_changes(world, last_run, this_run, changes);
+    }
+}
+
+/// Comparison applied by `Compare`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompareOp {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl CompareOp {
+    #[inline]
+    pub fn apply(self, left: f64, right: f64) -> bool {
+        match self {
+            CompareOp::Less => left < right,
+            CompareOp::LessOrEqual => left <= right,
+            CompareOp::Greater => left > right,
+            CompareOp::GreaterOrEqual => left >= right,
+            CompareOp::Equal => left == right,
+            CompareOp::NotEqual => left != right,
+        }
+    }
+}
+
+/// Cross-parameter condition: passes while `left op right` holds.
+/// e.g. flee when health/max_health < 0.25:
+/// `Compare::new(Ratio(ParamValue::<Health>::new(), ParamValue::<MaxHealth>::new()), CompareOp::Less, Const(0.25))`
+#[derive(Component)]
+pub struct Compare<L: Operand, R: Operand> {
+    pub left: L,
+    pub op: CompareOp,
+    pub right: R,
+}
+
+impl<L: Operand, R: Operand> Compare<L, R> {
+    #[inline]
+    pub fn new(left: L, op: CompareOp, right: R) -> Self { Self { left, op, right } }
+
+    /// Evaluates the comparison; fails when either side is unavailable.
+    pub fn is_satisfied(&self, context: &OperandContext) -> bool {
+        match (self.left.value(context), self.right.value(context)) {
+            (Some(left), Some(right)) => self.op.apply(left, right),
+            _ => false,
+        }
+    }
+}
+
+/// Queries used by `apply_compare_guards` for `Compare<L, R>`.
+#[derive(SystemParam)]
+pub struct CompareGuardQueries<'w, 's, L: Operand, R: Operand> {
+    edges: ConditionEdges<'w, 's, Compare<L, R>>,
+    reconfigured: EdgeReconfigurations<'w, 's, Compare<L, R>>,
+    child_of: Query<'w, 's, &'static StateChildOf>,
+    removed_conditions: RemovedComponents<'w, 's, Compare<L, R>>,
+    ticks: SystemChangeTick,
+    /// The entities each edge is currently indexed at, so stale entries can be pruned.
+    indexed: Local<'s, HashMap<Entity, Vec<Entity>>>,
+}
+
+/// Update Guards on edges with `Compare<L, R>`.
+/// Change-driven like `apply_param_guards`: parameter operands index their edges in
+/// `ParamDependents`, so edges are only re-evaluated when they are reconfigured or a parameter
+/// they read changes. A change to a resource operand re-evaluates every `Compare<L, R>` edge.
+/// `Guards` is only touched when the result flips.
+pub fn apply_compare_guards<L: Operand, R: Operand>(
+    world: &World,
+    mut queries: CompareGuardQueries<L, R>,
+    mut commands: Commands,
+) {
+    let key = format!("compare::<{}>", std::any::type_name::<Compare<L, R>>());
+    let mut dirty: HashSet<Entity> = HashSet::new();
+
+    // Edges that lost their condition (or were despawned): lift the guard and unindex them
+    for edge in queries.removed_conditions.read() {
+        let holders = queries.indexed.remove(&edge).unwrap_or_default();
+        L::unindex(edge, &holders, &mut commands);
+        R::unindex(edge, &holders, &mut commands);
+        if queries.edges.contains(edge) { continue; }
+        if world.get::<Guards>(edge).is_some_and(|g| g.has_guard(key.as_str())) {
+            let key = key.clone();
+            commands.entity(edge).entry::<Guards>().and_modify(move |mut guards| { guards.remove_guard(key.as_str()); });
+        }
+    }
+
+    // New or reconfigured edges: evaluate them and index them at every entity they may read from
+    for edge in queries.reconfigured.collect(&queries.edges, &queries.child_of) {
+        dirty.insert(edge);
+        let holders = queries.indexed.remove(&edge).unwrap_or_default();
+        L::unindex(edge, &holders, &mut commands);
+        R::unindex(edge, &holders, &mut commands);
+        let Ok((_, Source(source), _, scope)) = queries.edges.get(edge) else { continue; };
+        let holders = scope.copied().unwrap_or_default().candidates(*source, &queries.child_of);
+        L::index(edge, &holders, &mut commands);
+        R::index(edge, &holders, &mut commands);
+        queries.indexed.insert(edge, holders);
+    }
+
+    // Operands whose inputs changed
+    let mut changes = OperandChanges::default();
+    let (last_run, this_run) = (queries.ticks.last_run(), queries.ticks.this_run());
+    L::collect_changes(world, last_run, this_run, &mut changes);
+    R::collect_changes(world, last_run, this_run, &mut changes);
+    if changes.all {
+        dirty.extend(queries.edges.iter().map(|(edge, ..)| edge));
+    } else {
+        dirty.extend(changes.edges);
+        if !changes.despawned.is_empty() {
+            dirty.extend(queries.indexed.iter()
+                .filter(|(_, holders)| holders.iter().any(|h| changes.despawned.contains(h)))
+                .map(|(&edge, _)| edge));
+        }
+    }
+
+    for edge in dirty {
+        // Other conditions share the parameter index; only handle `Compare<L, R>` edges
+        let Ok((_, Source(source), condition, scope)) = queries.edges.get(edge) else { continue; };
+        let context = OperandContext {
+            world,
+            source: *source,
+            scope: scope.copied().unwrap_or_default(),
+            child_of: &queries.child_of,
+        };
+        let desired_blocked = !condition.is_satisfied(&context);
+        let current_has = world.get::<Guards>(edge).is_some_and(|g| g.has_guard(key.as_str()));
+        if desired_blocked == current_has { continue; }
+
+        let key = key.clone();
+        commands
+            .entity(edge)
+            .entry::<Guards>()
+            .or_default()
+            .and_modify(move |mut guards| {
+                if desired_blocked { guards.add_guard(key.as_str()); }
+                else { guards.remove_guard(key.as_str()); }
+            });
+    }
+}
+
+/// An animator-style trigger parameter. Setting it enables edges guarded by `TriggerSet<P>`
+/// once; it is cleared when such an edge fires, or otherwise at the end of the first frame in
+/// which `apply_trigger_param_guards` saw it set. A trigger set after the guards ran (e.g. in
+/// `PostUpdate`) is kept until the next frame's guard pass, so it is never silently lost.
+#[derive(Component)]
+pub struct TriggerParam<P> {
+    set: bool,
+    /// Whether the guards have evaluated the trigger since it was set.
+    observed: bool,
+    _marker: PhantomData<P>,
+}
+
+impl<P> Default for TriggerParam<P> {
+    fn default() -> Self { Self { set: false, observed: false, _marker: PhantomData } }
+}
+
+impl<P> TriggerParam<P> {
+    #[inline]
+    pub fn is_set(&self) -> bool { self.set }
+    #[inline]
+    pub fn set(&mut self) { self.set = true; self.observed = false; }
+    #[inline]
+    pub fn reset(&mut self) { self.set = false; self.observed = false; }
+}
+
+/// Trigger condition: passes while `TriggerParam<P>` is set on the entity the edge's
+/// `ParamScope` resolves to (the machine root by default).
+/// Edges start blocked so they cannot fire before the guard is first applied.
 #[derive(Component, Clone, Copy)]
-pub struct BoolEquals<P> {
-    pub expected: bool,
+#[component(on_add = block_trigger_edge::<P>)]
+pub struct TriggerSet<P: Send + Sync + 'static> {
     _marker: PhantomData<P>,
 }
 
-impl<P> BoolEquals<P> {
-    pub fn new(expected: bool) -> Self { Self { expected, _marker: PhantomData } }
+impl<P: Send + Sync + 'static> Default for TriggerSet<P> {
+    fn default() -> Self { Self { _marker: PhantomData } }
 }
 
-fn guard_key_for_bool<P>() -> String { format!("bool-equals::<{}>", std::any::type_name::<P>()) }
+fn guard_key_for_trigger<P>() -> String { format!("trigger-set::<{}>", std::any::type_name::<P>()) }
 
-/// Update Guards on edges with BoolEquals<P> based on the current BoolParam<P> value.
-pub fn apply_bool_param_guards<P: Send + Sync + 'static>(
-    q_edges: Query<(Entity, &Source, &BoolEquals<P>)>,
-    q_params: Query<&BoolParam<P>>,
+fn block_trigger_edge<P: Send + Sync + 'static>(mut world: DeferredWorld, context: HookContext) {
+    let key = guard_key_for_trigger::<P>();
+    if let Some(mut guards) = world.get_mut::<Guards>(context.entity) {
+        guards.add_guard(key.as_str());
+    } else {
+        world.commands().entity(context.entity).insert(Guards::init([key.as_str()]));
+    }
+}
+
+/// Update Guards on edges with TriggerSet<P> based on the current TriggerParam<P>.
+/// Should run in `ParamSystems::ApplyGuards` so a trigger set this frame can fire
+/// an Always edge before `reset_trigger_params` clears it. Marks the triggers it read as
+/// observed, making them eligible for that reset.
+pub fn apply_trigger_param_guards<P: Send + Sync + 'static>(
+    q_edges: ScopedEdges<With<TriggerSet<P>>>,
+    mut q_params: Query<&mut TriggerParam<P>>,
     q_child_of: Query<&StateChildOf>,
     mut q_guards: Query<&mut Guards>,
     mut commands: Commands,
-){
-    let key = guard_key_for_bool::<P>();
-    for (edge, Source(source), eq) in &q_edges {
-        let root = q_child_of.root_ancestor(*source);
-        let desired_blocked = match q_params.get(root) {
-            Ok(param) => param.get() != eq.expected,
-            Err(_) => true,
+) {
+    let key = guard_key_for_trigger::<P>();
+    for (edge, Source(source), scope) in &q_edges {
+        let holder = scope
+            .copied()
+            .unwrap_or_default()
+            .resolve(*source, &q_child_of, |e| q_params.contains(e));
+        let desired_blocked = match holder.and_then(|e| q_params.get_mut(e).ok()) {
+            Some(mut param) => {
+                if param.set && !param.observed {
+                    param.bypass_change_detection().observed = true;
+                }
+                !param.set
+            }
+            None => true,
         };
+        set_guard_blocked(edge, key.as_str(), desired_blocked, &mut q_guards, &mut commands);
+    }
+}
 
-        let current_has = q_guards
-            .get(edge)
-            .ok()
-            .map(|g| g.has_guard(key.as_str()))
-            .unwrap_or(false);
-
-        if desired_blocked != current_has {
-            if let Ok(mut g) = q_guards.get_mut(edge) {
-                if desired_blocked { g.add_guard(key.as_str()); }
-                else { g.remove_guard(key.as_str()); }
-            } else if desired_blocked {
-                commands.entity(edge).insert(Guards::init([key.as_str()]));
+/// Consume the trigger when an edge guarded by TriggerSet<P> fires. Every TriggerSet<P> edge
+/// of the same machine is blocked again right away, so states entered by this transition
+/// cannot reuse the trigger.
+pub fn consume_trigger_on_transition<P: Send + Sync + 'static>(
+    transition_actions: On<TransitionActions>,
+    q_edges: ScopedEdges<With<TriggerSet<P>>>,
+    q_child_of: Query<&StateChildOf>,
+    mut q_params: Query<&mut TriggerParam<P>>,
+    mut q_guards: Query<&mut Guards>,
+    mut commands: Commands,
+) {
+    let Ok((_, Source(source), scope)) = q_edges.get(transition_actions.target) else { return; };
+    let holder = scope
+        .copied()
+        .unwrap_or_default()
+        .resolve(*source, &q_child_of, |e| q_params.contains(e));
+    if let Some(mut param) = holder.and_then(|e| q_params.get_mut(e).ok()) {
+        param.reset();
+    }
+
+    let root = q_child_of.root_ancestor(*source);
+    let key = guard_key_for_trigger::<P>();
+    for (edge, Source(source), _) in &q_edges {
+        if q_child_of.root_ancestor(*source) != root { continue; }
+        set_guard_blocked(edge, key.as_str(), true, &mut q_guards, &mut commands);
+    }
+}
+
+/// End-of-frame system: clears triggers the guards saw set this frame but no edge consumed.
+/// Triggers set after the guard pass survive until the next frame's.
+pub fn reset_trigger_params<P: Send + Sync + 'static>(
+    mut q_params: Query<&mut TriggerParam<P>>,
+) {
+    for mut param in &mut q_params {
+        if param.set && param.observed { param.reset(); }
+    }
+}
+
+/// System sets for parameter systems in `Update`. Bindings sync before guards are applied,
+/// and guards are applied before `check_always_on_guards_changed` re-checks Always edges.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParamSystems {
+    Sync,
+    ApplyGuards,
+}
+
+/// Internal resource to dedupe per-type parameter installation.
+#[derive(Resource, Default)]
+pub struct InstalledParams(pub HashSet<TypeId>);
+
+/// Installer record collected via `inventory` for auto-registration of parameter types.
+/// Submitted by `register_param_binding!`, `register_param_condition!`,
+/// `register_compare_condition!` and `register_trigger_param!`.
+pub struct ParamInstaller {
+    pub install: fn(&mut App),
+}
+
+inventory::collect!(ParamInstaller);
+
+/// Returns true the first time it is called for `K` on this app.
+fn first_install<K: 'static>(app: &mut App) -> bool {
+    app.world_mut()
+        .get_resource_or_init::<InstalledParams>()
+        .0
+        .insert(TypeId::of::<K>())
+}
+
+/// Adds `sync_param::<S, P, T>` once per app.
+pub fn register_param_binding<S, P, T>(app: &mut App)
+where
+    S: Component,
+    P: ParamBinding<S, T> + Send + Sync + 'static,
+    T: PartialEq + Send + Sync + 'static,
+{
+    if !first_install::<(S, P, T)>(app) { return; }
+    app.add_systems(Update, sync_param::<S, P, T>.in_set(ParamSystems::Sync));
+}
+
+/// Adds `apply_param_guards::<C>` once per app.
+pub fn register_param_condition<C: ParamCondition>(app: &mut App) {
+    if !first_install::<C>(app) { return; }
+    app.add_systems(Update, apply_param_guards::<C>.in_set(ParamSystems::ApplyGuards));
+}
+
+/// Adds `apply_compare_guards::<L, R>` once per app.
+pub fn register_compare_condition<L: Operand, R: Operand>(app: &mut App) {
+    if !first_install::<Compare<L, R>>(app) { return; }
+    app.add_systems(Update, apply_compare_guards::<L, R>.in_set(ParamSystems::ApplyGuards));
+}
+
+/// Adds the trigger guard application, consumption observer and end-of-frame reset once per app.
+pub fn register_trigger_param<P: Send + Sync + 'static>(app: &mut App) {
+    if !first_install::<TriggerParam<P>>(app) { return; }
+    app.add_systems(Update, apply_trigger_param_guards::<P>.in_set(ParamSystems::ApplyGuards))
+        .add_systems(Last, reset_trigger_params::<P>)
+        .add_observer(consume_trigger_on_transition::<P>);
+}
+
+/// Helper trait to add parameter systems to an App.
+pub trait ParamAppExt {
+    /// Registers `sync_param::<S, P, T>`.
+    fn add_param_binding<S, P, T>(&mut self) -> &mut Self
+    where
+        S: Component,
+        P: ParamBinding<S, T> + Send + Sync + 'static,
+        T: PartialEq + Send + Sync + 'static;
+
+    /// Registers `apply_param_guards::<C>`.
+    fn add_param_condition<C: ParamCondition>(&mut self) -> &mut Self;
+
+    /// Registers `apply_compare_guards::<L, R>`.
+    fn add_compare_condition<L: Operand, R: Operand>(&mut self) -> &mut Self;
+
+    /// Registers the guard application, consumption observer and end-of-frame reset
+    /// for `TriggerParam<P>`.
+    fn add_trigger_param<P: Send + Sync + 'static>(&mut self) -> &mut Self;
+}
+
+impl ParamAppExt for App {
+    fn add_param_binding<S, P, T>(&mut self) -> &mut Self
+    where
+        S: Component,
+        P: ParamBinding<S, T> + Send + Sync + 'static,
+        T: PartialEq + Send + Sync + 'static,
+    {
+        register_param_binding::<S, P, T>(self);
+        self
+    }
+
+    fn add_param_condition<C: ParamCondition>(&mut self) -> &mut Self {
+        register_param_condition::<C>(self);
+        self
+    }
+
+    fn add_compare_condition<L: Operand, R: Operand>(&mut self) -> &mut Self {
+        register_compare_condition::<L, R>(self);
+        self
+    }
+
+    fn add_trigger_param<P: Send + Sync + 'static>(&mut self) -> &mut Self {
+        register_trigger_param::<P>(self);
+        self
+    }
+}
+
+/// Auto-registers `sync_param::<S, P, T>` with `GearboxPlugin` through `inventory`.
+/// Usage: `register_param_binding!(Hitpoints, IsDead, bool);`
+#[macro_export]
+macro_rules! register_param_binding {
+    ($source:ty, $param:ty, $value:ty) => {
+        $crate::inventory::submit! {
+            $crate::parameter::ParamInstaller {
+                install: |app| $crate::parameter::register_param_binding::<$source, $param, $value>(app),
             }
         }
-    }
-}
\ No newline at end of file
+    };
+}
+
+/// Auto-registers `apply_param_guards::<C>` with `GearboxPlugin` through `inventory`.
+/// Usage: `register_param_condition!(BoolEquals<IsDead>);`
+#[macro_export]
+macro_rules! register_param_condition {
+    ($condition:ty) => {
+        $crate::inventory::submit! {
+            $crate::parameter::ParamInstaller {
+                install: |app| $crate::parameter::register_param_condition::<$condition>(app),
+            }
+        }
+    };
+}
+
+/// Auto-registers `apply_compare_guards::<L, R>` with `GearboxPlugin` through `inventory`.
+/// Usage: `register_compare_condition!(ParamValue<Stamina>, ParamValue<SprintCost>);`
+#[macro_export]
+macro_rules! register_compare_condition {
+    ($left:ty, $right:ty) => {
+        $crate::inventory::submit! {
+            $crate::parameter::ParamInstaller {
+                install: |app| $crate::parameter::register_compare_condition::<$left, $right>(app),
+            }
+        }
+    };
+}
+
+/// Auto-registers the systems for `TriggerParam<P>` with `GearboxPlugin` through `inventory`.
+/// Usage: `register_trigger_param!(Jump);`
+#[macro_export]
+macro_rules! register_trigger_param {
+    ($param:ty) => {
+        $crate::inventory::submit! {
+            $crate::parameter::ParamInstaller {
+                install: |app| $crate::parameter::register_trigger_param::<$param>(app),
+            }
+        }
+    };
+}
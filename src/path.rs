@@ -0,0 +1,128 @@
+use std::fmt;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{transitions::{Source, Target}, InitialState, StateChildOf, StateChildren, StateMachine};
+
+/// A `/`-separated path to a state from its machine root, e.g. `"Alive/Locomotion/Running"`.
+/// Each segment matches the first child (in `StateChildren` order) with that `Name`;
+/// `#n` matches the n-th child, which also addresses unnamed states.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+pub struct StatePath(pub Vec<String>);
+
+impl StatePath {
+    pub fn parse(path: &str) -> Self {
+        Self(path.split('/').filter(|segment| !segment.is_empty()).map(str::to_string).collect())
+    }
+
+    pub fn segments(&self) -> &[String] {
+        &self.0
+    }
+}
+
+impl From<&str> for StatePath {
+    fn from(path: &str) -> Self { Self::parse(path) }
+}
+
+impl fmt::Display for StatePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.join("/"))
+    }
+}
+
+/// Authoring alternative to `Target`: resolved to `Target` when the machine starts.
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component)]
+pub struct TargetPath(pub String);
+
+/// Authoring alternative to `InitialState`: resolved to `InitialState` when the machine starts.
+/// The path is relative to the machine root, like every `StatePath`.
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component)]
+pub struct InitialStatePath(pub String);
+
+/// Resolve states by path and describe states as paths.
+#[derive(SystemParam)]
+pub struct StatePaths<'w, 's> {
+    children: Query<'w, 's, &'static StateChildren>,
+    child_of: Query<'w, 's, &'static StateChildOf>,
+    names: Query<'w, 's, &'static Name>,
+    machines: Query<'w, 's, &'static StateMachine>,
+}
+
+impl StatePaths<'_, '_> {
+    /// The state at `path` below `root`, e.g. `find_state(root, "Alive/Standing")`.
+    pub fn find_state(&self, root: Entity, path: impl Into<StatePath>) -> Option<Entity> {
+        let path = path.into();
+        let mut state = root;
+        for segment in path.segments() {
+            let children = self.children.get(state).ok()?;
+            state = children.into_iter().copied()
+                .find(|&child| self.names.get(child).is_ok_and(|name| name.as_str() == segment))
+                .or_else(|| {
+                    let index: usize = segment.strip_prefix('#')?.parse().ok()?;
+                    children.into_iter().nth(index).copied()
+                })?;
+        }
+        Some(state)
+    }
+
+    /// The path of `state` from its machine root. Unnamed states appear as `#n`.
+    pub fn path_of(&self, state: Entity) -> StatePath {
+        let mut segments = Vec::new();
+        let mut current = state;
+        while let Ok(StateChildOf(parent)) = self.child_of.get(current) {
+            let segment = match self.names.get(current) {
+                Ok(name) => name.as_str().to_string(),
+                Err(_) => {
+                    let index = self.children.get(*parent).ok()
+                        .and_then(|children| children.into_iter().position(|&c| c == current))
+                        .unwrap_or_default();
+                    format!("#{index}")
+                }
+            };
+            segments.push(segment);
+            current = *parent;
+        }
+        segments.reverse();
+        StatePath(segments)
+    }
+
+    /// Paths of the machine's active leaves, sorted. Handy in logs and assertion messages.
+    pub fn active_leaves(&self, root: Entity) -> Vec<StatePath> {
+        let Ok(machine) = self.machines.get(root) else { return Vec::new(); };
+        let mut paths: Vec<StatePath> = machine.active_leaves.iter().map(|&leaf| self.path_of(leaf)).collect();
+        paths.sort_by_key(|path| path.to_string());
+        paths
+    }
+}
+
+/// `TargetPath`s and `InitialStatePath`s waiting to be resolved.
+#[derive(SystemParam)]
+pub(crate) struct PathAuthoring<'w, 's> {
+    paths: StatePaths<'w, 's>,
+    target_paths: Query<'w, 's, (Entity, &'static TargetPath, &'static Source)>,
+    initial_paths: Query<'w, 's, (Entity, &'static InitialStatePath)>,
+}
+
+impl PathAuthoring<'_, '_> {
+    /// Insert `Target`s and `InitialState`s for the paths authored in the machine at `root`.
+    pub(crate) fn resolve(&self, root: Entity, commands: &mut Commands) {
+        let in_machine = |state: Entity| self.paths.child_of.root_ancestor(state) == root;
+
+        for (edge, TargetPath(path), source) in self.target_paths.iter() {
+            if !in_machine(source.0) { continue; }
+            match self.paths.find_state(root, path.as_str()) {
+                Some(target) => { commands.entity(edge).insert(Target(target)); }
+                None => warn!("bevy_gearbox: TargetPath \"{path}\" on edge {edge} matches no state"),
+            }
+        }
+        for (state, InitialStatePath(path)) in self.initial_paths.iter() {
+            if !in_machine(state) { continue; }
+            match self.paths.find_state(root, path.as_str()) {
+                Some(initial) => { commands.entity(state).insert(InitialState(initial)); }
+                None => warn!("bevy_gearbox: InitialStatePath \"{path}\" on state {state} matches no state"),
+            }
+        }
+    }
+}
@@ -14,24 +14,62 @@ pub use crate::{
     StateChildren,
     StateMachine,
     transitions::DeferEvent,
+    transitions::DeferOverflow,
+    transitions::Paused,
+    transitions::PausedEvents,
     guards::Guards,
     history::HistoryState,
     InitialState,
     state_component::StateComponent,
     Parallel,
+    FinalState,
+    invoke::Invoke,
+    invoke::InvokeExit,
+    invoke::InvokedMachine,
+    invoke::InvokedBy,
+    invoke::InvokeDone,
+    invoke::ForwardEvents,
     state_component::StateInactiveComponent,
+    state_component::ActiveProviders,
+    state_component::StateBundle,
+    state_component::StateComponentTarget,
+    state_component::StateReflectComponents,
+    state_resource::StateResource,
+    state_resource::StateResourceProviders,
+    state_systems::StateContext,
+    resettable::Resettable,
     transitions::After,
+    transitions::DelaySource,
+    transitions::ChartRng,
+    transitions::Every,
+    transitions::ChartTimers,
+    transitions::TimedEdge,
+    transitions::TimedEdgeKind,
+    transitions::EveryTimer,
+    transitions::TimeScale,
+    transitions::ChartClock,
+    transitions::TimerClock,
+    transitions::TimerSettings,
+    transitions::AfterTimerSystems,
     // Enums
     history::History,
     // Traits
     guards::Guard,
     state_component::StateComponentAppExt,
+    state_resource::StateResourceAppExt,
+    state_systems::StateSystemsAppExt,
+    state_systems::IntoStateSystems,
+    resettable::ResettableAppExt,
     // Systems
     get_all_leaf_states,
     state_component::state_component_enter,
     state_component::state_component_exit,
     state_component::state_inactive_component_enter,
     state_component::state_inactive_component_exit,
+    state_component::state_bundle_enter,
+    state_component::state_bundle_exit,
+    state_resource::state_resource_enter,
+    state_resource::state_resource_exit,
     transitions::Transitions,
     transitions::Source,
     transitions::Target,
@@ -44,6 +82,21 @@ pub use crate::{
     // Bevy state integration
     bevy_state::AppBevyStateBridgeExt,
     bevy_state::GearboxCommandsExt,
+    bevy_state::StateBridge,
+    chart_states::ChartStates,
+    chart_states::in_chart_state,
+    repair::StateDespawnExt,
+    path::StatePath,
+    path::StatePaths,
+    path::TargetPath,
+    path::InitialStatePath,
+    edit::ChartEditCommandsExt,
+    // Cross-machine sending
+    send::send_to_charts,
+    send::ChartRoute,
+    send::ChartSendCommandsExt,
+    send::ChartSendReport,
+    send::SendReport,
     // Derive macros
     SimpleTransition,
 };
@@ -52,22 +105,73 @@ pub use bevy_gearbox_macros::register_transition;
 
 pub use crate::parameter::{
     // Parameter components
+    Param,
     FloatParam,
     IntParam,
     BoolParam,
+    TriggerParam,
+    ParamScope,
+    ParamFromRelationship,
     // Parameter binding traits
+    ParamBinding,
     FloatParamBinding,
     IntParamBinding,
     BoolParamBinding,
     // Sync systems
+    sync_param,
     sync_float_param,
     sync_int_param,
     sync_bool_param,
+    sync_param_scope_from_relationship,
     // Guard components and appliers
+    ParamDependents,
+    ConditionState,
+    ParamCondition,
+    Equals,
+    NotEquals,
+    InRange,
+    GreaterThan,
+    LessThan,
+    OneOf,
+    apply_param_guards,
     FloatInRange,
     apply_float_param_guards,
     IntInRange,
     apply_int_param_guards,
     BoolEquals,
     apply_bool_param_guards,
+    // Cross-parameter conditions
+    Compare,
+    CompareOp,
+    Operand,
+    OperandChanges,
+    OperandContext,
+    ParamValue,
+    ResourceValue,
+    Const,
+    Sum,
+    Difference,
+    Product,
+    Ratio,
+    Abs,
+    apply_compare_guards,
+    TriggerSet,
+    apply_trigger_param_guards,
+    consume_trigger_on_transition,
+    reset_trigger_params,
+    // App extension and system sets
+    ParamAppExt,
+    ParamSystems,
+};
+
+pub use crate::{
+    register_param_binding,
+    register_param_condition,
+    register_compare_condition,
+    register_trigger_param,
+    register_state_component,
+    register_state_inactive_component,
+    register_state_bundle,
+    register_state_resource,
+    register_resettable,
 };
\ No newline at end of file
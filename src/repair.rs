@@ -0,0 +1,230 @@
+use bevy::{ecs::system::{RunSystemOnce, SystemParam}, platform::collections::{HashMap, HashSet}, prelude::*};
+
+use crate::{
+    compute_active_from_leaves, get_all_leaf_states,
+    history::{History, HistoryState},
+    transitions::{Target, Transitions},
+    EnterState, ExitState, InitialState, Parallel, StateChildOf, StateChildren, StateMachine,
+};
+
+/// Keeps a running machine consistent when one of its states is despawned or detached.
+/// Active states in the removed subtree are exited (deepest first) and dropped from the
+/// machine; if that leaves the parent without an active child, the parent falls back to its
+/// initial (or history) state. `InitialState`, `HistoryState` and edge `Target`s of the machine
+/// that pointed into the subtree are stripped with a warning.
+///
+/// Removals are batched per machine and repaired by one command. Machines that aren't running,
+/// or whose root is itself being despawned, are skipped.
+///
+/// With a plain despawn, `ExitState` for the removed states fires after they are gone: exit
+/// observers can't read the exited state's components, so effects such as `StateComponent`
+/// aren't undone. `despawn_state` exits first, then despawns.
+pub(crate) fn repair_on_state_removed(
+    remove: On<Remove, StateChildOf>,
+    q_child_of: Query<&StateChildOf>,
+    q_children: Query<&StateChildren>,
+    q_sm: Query<&StateMachine>,
+    mut pending: ResMut<PendingRepairs>,
+    mut commands: Commands,
+) {
+    let state = remove.entity;
+    let Ok(StateChildOf(parent)) = q_child_of.get(state) else { return; };
+    let parent = *parent;
+    let root = q_child_of.root_ancestor(parent);
+    let Ok(machine) = q_sm.get(root) else { return; };
+
+    let mut removed = vec![state];
+    removed.extend(q_children.iter_descendants(state));
+    let exits = active_exit_order(&removed, machine, &q_child_of);
+
+    let repairs = pending.0.entry(root).or_default();
+    if repairs.is_empty() {
+        commands.queue(move |world: &mut World| run_pending_repairs(world, root));
+    }
+    repairs.push(PendingRepair { parent, removed, exits });
+}
+
+/// States removed from running machines since the last repair, by machine root.
+#[derive(Resource, Default)]
+pub(crate) struct PendingRepairs(HashMap<Entity, Vec<PendingRepair>>);
+
+pub(crate) struct PendingRepair {
+    parent: Entity,
+    removed: Vec<Entity>,
+    exits: Vec<Entity>,
+}
+
+fn run_pending_repairs(world: &mut World, root: Entity) {
+    let Some(repairs) = world.resource_mut::<PendingRepairs>().0.remove(&root) else { return; };
+    if world.get::<StateMachine>(root).is_none() { return; }
+    let removed: Vec<Entity> = repairs.iter().flat_map(|repair| repair.removed.iter().copied()).collect();
+    strip_stale_references(world, root, &removed);
+    for repair in repairs {
+        exit_states(world, root, repair.parent, &repair.exits);
+    }
+}
+
+/// Active members of `removed`, deepest first.
+pub(crate) fn active_exit_order(removed: &[Entity], machine: &StateMachine, q_child_of: &Query<&StateChildOf>) -> Vec<Entity> {
+    let mut exits: Vec<Entity> = removed.iter().copied().filter(|s| machine.active.contains(s)).collect();
+    exits.sort_by_key(|&s| std::cmp::Reverse(q_child_of.iter_ancestors(s).count()));
+    exits
+}
+
+fn repair_machine(world: &mut World, root: Entity, parent: Entity, removed: &[Entity], exits: &[Entity]) {
+    strip_stale_references(world, root, removed);
+    exit_states(world, root, parent, exits);
+}
+
+/// Exit `exits` (deepest first) and drop them from the machine. If `parent` is left without an
+/// active child, it falls back to its initial (or history) state.
+pub(crate) fn exit_states(world: &mut World, root: Entity, parent: Entity, exits: &[Entity]) {
+    if exits.is_empty() { return; }
+
+    exit_active(world, root, exits);
+    let Some(machine) = world.get::<StateMachine>(root) else { return; };
+    let active: Vec<Entity> = machine.active.iter().copied().collect();
+    let parent_has_active_child = active.iter()
+        .any(|&s| world.get::<StateChildOf>(s).is_some_and(|c| c.0 == parent));
+    if parent_has_active_child { return; }
+
+    if let Some(mut machine) = world.get_mut::<StateMachine>(root) {
+        machine.active_leaves.insert(parent);
+    }
+    let _ = world.run_system_once_with(enter_below, (root, parent, Vec::new()));
+}
+
+/// Trigger `ExitState` for `exits` (deepest first) and drop them from the machine.
+pub(crate) fn exit_active(world: &mut World, root: Entity, exits: &[Entity]) {
+    for &state in exits {
+        world.trigger(ExitState { target: state, state_machine: root });
+    }
+    world.flush();
+
+    let Some(mut machine) = world.get_mut::<StateMachine>(root) else { return; };
+    for state in exits {
+        machine.active.remove(state);
+        machine.active_leaves.remove(state);
+    }
+}
+
+/// Queries `get_all_leaf_states` walks to drill into a state.
+#[derive(SystemParam)]
+pub(crate) struct EntryQueries<'w, 's> {
+    initial_state: Query<'w, 's, &'static InitialState>,
+    children: Query<'w, 's, &'static StateChildren>,
+    parallel: Query<'w, 's, &'static Parallel>,
+    history: Query<'w, 's, &'static History>,
+    history_state: Query<'w, 's, &'static mut HistoryState>,
+    child_of: Query<'w, 's, &'static StateChildOf>,
+}
+
+/// Enter `children` of an active `parent` and drill into them. With no children, drill from
+/// `parent` itself if it is an active leaf, i.e. fall back to its initial (or history) state.
+pub(crate) fn enter_below(
+    In((root, parent, children)): In<(Entity, Entity, Vec<Entity>)>,
+    mut q_sm: Query<&mut StateMachine>,
+    entry: EntryQueries,
+    mut commands: Commands,
+) {
+    let Ok(mut machine) = q_sm.get_mut(root) else { return; };
+    let starts = if children.is_empty() {
+        if !machine.active_leaves.contains(&parent) { return; }
+        vec![parent]
+    } else {
+        for &child in &children {
+            commands.trigger(EnterState { target: child, state_machine: root });
+        }
+        children
+    };
+
+    machine.active_leaves.remove(&parent);
+    for start in starts {
+        let leaves = get_all_leaf_states(
+            start,
+            root,
+            &entry.initial_state,
+            &entry.children,
+            &entry.parallel,
+            &entry.history,
+            &entry.history_state,
+            &entry.child_of,
+            &mut commands,
+        );
+        machine.active_leaves.extend(leaves);
+    }
+    machine.active = compute_active_from_leaves(&machine.active_leaves, &entry.child_of);
+}
+
+/// Remove `InitialState`, `HistoryState` entries and edge `Target`s of `root`'s chart
+/// pointing at removed states.
+fn strip_stale_references(world: &mut World, root: Entity, removed: &[Entity]) {
+    let removed: HashSet<Entity> = removed.iter().copied().collect();
+    let mut q_children = world.query::<&StateChildren>();
+    let states: Vec<Entity> = std::iter::once(root)
+        .chain(q_children.query(world).iter_descendants(root))
+        .filter(|state| !removed.contains(state))
+        .collect();
+    let edges: Vec<Entity> = states.iter()
+        .filter_map(|&state| world.get::<Transitions>(state))
+        .flat_map(|transitions| transitions.into_iter().copied())
+        .collect();
+
+    for &owner in &states {
+        if world.get::<InitialState>(owner).is_some_and(|initial| removed.contains(&initial.0)) {
+            warn!("bevy_gearbox: removed InitialState of {owner} pointing at a removed state");
+            world.entity_mut(owner).remove::<InitialState>();
+        }
+
+        let Some(mut history_state) = world.get_mut::<HistoryState>(owner) else { continue; };
+        if !history_state.0.iter().any(|s| removed.contains(s)) { continue; }
+        warn!("bevy_gearbox: dropped removed states from HistoryState of {owner}");
+        history_state.0.retain(|s| !removed.contains(s));
+        if history_state.0.is_empty() {
+            world.entity_mut(owner).remove::<HistoryState>();
+        }
+    }
+
+    for edge in edges {
+        if world.get::<Target>(edge).is_some_and(|target| removed.contains(&target.0)) {
+            warn!("bevy_gearbox: removed Target of edge {edge} pointing at a removed state");
+            world.entity_mut(edge).remove::<Target>();
+        }
+    }
+}
+
+/// Despawn a state after exiting it, so exit observers still see its components.
+pub trait StateDespawnExt {
+    /// Exit this state and its active descendants, repair the machine, then despawn the subtree.
+    fn despawn_state(&mut self);
+}
+
+impl StateDespawnExt for EntityCommands<'_> {
+    fn despawn_state(&mut self) {
+        let state = self.id();
+        self.commands().queue(move |world: &mut World| {
+            let Some(StateChildOf(parent)) = world.get::<StateChildOf>(state) else {
+                // Not part of a chart, or already despawned before the command ran
+                if let Ok(entity) = world.get_entity_mut(state) {
+                    entity.despawn();
+                }
+                return;
+            };
+            let parent = *parent;
+            let mut q_child_of = world.query::<&StateChildOf>();
+            let mut q_children = world.query::<&StateChildren>();
+            let q_child_of = q_child_of.query(world);
+            let root = q_child_of.root_ancestor(parent);
+            let mut removed = vec![state];
+            removed.extend(q_children.query(world).iter_descendants(state));
+            let exits = world.get::<StateMachine>(root)
+                .map(|machine| active_exit_order(&removed, machine, &q_child_of))
+                .unwrap_or_default();
+
+            repair_machine(world, root, parent, &removed, &exits);
+            if let Ok(entity) = world.get_entity_mut(state) {
+                entity.despawn();
+            }
+        });
+    }
+}
@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+
+use crate::{state_component::{first_install, Reset}, StateChildOf, StateChildren, StateMachine};
+
+/// Snapshots `T` on the same state entity when its machine starts, and restores the
+/// snapshot whenever a `Reset` targets that entity (e.g. from a `ResetEdge`).
+#[derive(Component)]
+pub struct Resettable<T: Component + Clone> {
+    initial: Option<T>,
+}
+
+impl<T: Component + Clone> Default for Resettable<T> {
+    fn default() -> Self { Self { initial: None } }
+}
+
+impl<T: Component + Clone> Resettable<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The captured value, once the machine has started.
+    pub fn initial(&self) -> Option<&T> { self.initial.as_ref() }
+}
+
+/// Capture `T` for every `Resettable<T>` in a machine when it starts. Only the first start
+/// counts; machine resets keep the original snapshot.
+pub fn capture_resettables_on_start<T: Component + Clone>(
+    add: On<Add, StateMachine>,
+    q_children: Query<&StateChildren>,
+    mut q_resettable: Query<(&T, &mut Resettable<T>)>,
+) {
+    let root = add.event().entity;
+    for state in std::iter::once(root).chain(q_children.iter_descendants(root)) {
+        let Ok((value, mut resettable)) = q_resettable.get_mut(state) else { continue; };
+        if resettable.initial.is_none() {
+            resettable.initial = Some(value.clone());
+        }
+    }
+}
+
+/// Capture `T` right away when `Resettable<T>` is added to a state of a running machine.
+pub fn capture_resettable_on_add<T: Component + Clone>(
+    add: On<Add, Resettable<T>>,
+    q_child_of: Query<&StateChildOf>,
+    q_machine: Query<(), With<StateMachine>>,
+    mut q_resettable: Query<(&T, &mut Resettable<T>)>,
+) {
+    let state = add.event().entity;
+    if !q_machine.contains(q_child_of.root_ancestor(state)) { return; }
+    let Ok((value, mut resettable)) = q_resettable.get_mut(state) else { return; };
+    if resettable.initial.is_none() {
+        resettable.initial = Some(value.clone());
+    }
+}
+
+/// Restore the captured `T` when a `Reset` targets its entity.
+pub fn restore_resettable_on_reset<T: Component + Clone>(
+    reset: On<Reset>,
+    q_resettable: Query<&Resettable<T>>,
+    mut commands: Commands,
+) {
+    let entity = reset.target;
+    let Some(initial) = q_resettable.get(entity).ok().and_then(|r| r.initial.clone()) else { return; };
+    commands.entity(entity).insert(initial);
+}
+
+/// Helper trait to add `Resettable<T>` observers to an App.
+pub trait ResettableAppExt {
+    /// Registers the capture and restore observers for `Resettable<T>`.
+    fn add_resettable<T: Component + Clone>(&mut self) -> &mut Self;
+}
+
+impl ResettableAppExt for App {
+    fn add_resettable<T: Component + Clone>(&mut self) -> &mut Self {
+        register_resettable::<T>(self);
+        self
+    }
+}
+
+/// Adds the observers for `Resettable<T>` once per app.
+pub fn register_resettable<T: Component + Clone>(app: &mut App) {
+    if !first_install::<Resettable<T>>(app) { return; }
+    app.add_observer(capture_resettables_on_start::<T>)
+        .add_observer(capture_resettable_on_add::<T>)
+        .add_observer(restore_resettable_on_reset::<T>);
+}
+
+/// Auto-registers `Resettable<T>` observers with `GearboxPlugin` through `inventory`.
+/// Usage: `register_resettable!(Ammo);`
+#[macro_export]
+macro_rules! register_resettable {
+    ($component:ty) => {
+        $crate::inventory::submit! {
+            $crate::state_component::StateComponentInstaller {
+                install: |app| $crate::resettable::register_resettable::<$component>(app),
+            }
+        }
+    };
+}
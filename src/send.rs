@@ -0,0 +1,198 @@
+use std::time::Duration;
+
+use bevy::{ecs::relationship::RelationshipTarget, prelude::*};
+
+use crate::{transitions::{Source, TimerDelta}, StateChildOf, StateMachine, TransitionActions};
+
+type ResolveRoots = Box<dyn Fn(&mut World) -> Vec<Entity> + Send + Sync>;
+type PendingSend = Box<dyn FnOnce(&mut World) + Send + Sync>;
+
+/// Selects the machine roots an event is sent to.
+pub struct ChartRoute(ResolveRoots);
+
+impl ChartRoute {
+    /// Every machine root carrying marker `M`.
+    pub fn marked<M: Component>() -> Self {
+        Self(Box::new(|world: &mut World| {
+            world.query_filtered::<Entity, (With<M>, With<StateMachine>)>().iter(world).collect()
+        }))
+    }
+
+    /// Every machine with an active state carrying component `C`.
+    pub fn in_state_with<C: Component>() -> Self {
+        Self(Box::new(|world: &mut World| {
+            let mut q_sm = world.query::<(Entity, &StateMachine)>();
+            q_sm.iter(world)
+                .filter(|(_, machine)| machine.active.iter().any(|&s| world.get::<C>(s).is_some()))
+                .map(|(root, _)| root)
+                .collect()
+        }))
+    }
+
+    /// The machine roots related to `entity` through relationship `R`, e.g. the members of a squad.
+    pub fn related<R: RelationshipTarget>(entity: Entity) -> Self {
+        Self(Box::new(move |world: &mut World| {
+            world.get::<R>(entity).map(|related| related.iter().collect()).unwrap_or_default()
+        }))
+    }
+
+    /// An explicit list of machine roots.
+    pub fn roots(roots: impl IntoIterator<Item = Entity>) -> Self {
+        let roots: Vec<Entity> = roots.into_iter().collect();
+        Self(Box::new(move |_: &mut World| roots.clone()))
+    }
+
+    pub fn resolve(&self, world: &mut World) -> Vec<Entity> {
+        (self.0)(world)
+    }
+}
+
+/// Outcome of sending an event to machines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SendReport {
+    /// Machines the event was delivered to.
+    pub received: usize,
+    /// Machines in which the event fired at least one transition.
+    pub consumed: usize,
+}
+
+/// Triggered after a send issued through `Commands` completes.
+#[derive(Event, Clone, Debug)]
+pub struct ChartSendReport {
+    /// Type name of the event that was sent.
+    pub event: &'static str,
+    pub report: SendReport,
+}
+
+/// Edges that fired while a send is being tallied.
+#[derive(Resource, Default)]
+pub(crate) struct SendTally(Vec<Entity>);
+
+pub(crate) fn tally_transitions(transition_actions: On<TransitionActions>, tally: Option<ResMut<SendTally>>) {
+    if let Some(mut tally) = tally {
+        tally.0.push(transition_actions.target);
+    }
+}
+
+/// A send waiting for its delay to elapse.
+/// Ticks in `AfterTimerSystems` on the clock of machine `clock` (pause, `TimeScale` and
+/// `TimerClock` included), or on the app-wide `TimerSettings::clock` when `None`.
+#[derive(Component)]
+pub struct DelayedSend {
+    pub timer: Timer,
+    pub clock: Option<Entity>,
+    send: Option<PendingSend>,
+}
+
+pub(crate) fn tick_delayed_sends(
+    mut q_sends: Query<(Entity, &mut DelayedSend)>,
+    timer_delta: TimerDelta,
+    mut commands: Commands,
+) {
+    for (entity, mut delayed) in q_sends.iter_mut() {
+        let delta = match delayed.clock {
+            Some(root) => timer_delta.for_machine(root),
+            None => Some(timer_delta.for_app()),
+        };
+        let Some(delta) = delta else { continue; };
+        if !delayed.timer.tick(delta).just_finished() { continue; }
+        commands.entity(entity).despawn();
+        if let Some(send) = delayed.send.take() {
+            commands.queue(send);
+        }
+    }
+}
+
+/// Send a transition event to every machine selected by `route` and report the outcome.
+/// Each machine settles before the next one receives its event.
+pub fn send_to_charts<E>(world: &mut World, route: &ChartRoute, make: impl Fn(Entity) -> E) -> SendReport
+where
+    E: EntityEvent,
+    for<'a> <E as Event>::Trigger<'a>: Default,
+{
+    let mut report = SendReport::default();
+    for root in route.resolve(world) {
+        if world.get::<StateMachine>(root).is_none() { continue; }
+        world.insert_resource(SendTally::default());
+        world.trigger(make(root));
+        world.flush();
+        let Some(SendTally(edges)) = world.remove_resource::<SendTally>() else { continue; };
+        report.received += 1;
+        let mut q_child_of = world.query::<&StateChildOf>();
+        let q_child_of = q_child_of.query(world);
+        let consumed = edges.iter().any(|&edge| {
+            world.get::<Source>(edge).is_some_and(|source| q_child_of.root_ancestor(source.0) == root)
+        });
+        if consumed {
+            report.consumed += 1;
+        }
+    }
+    report
+}
+
+/// Commands for sending transition events across machines. Each send triggers `ChartSendReport`.
+pub trait ChartSendCommandsExt {
+    /// Send an event built by `make` to every machine root selected by `route`.
+    /// Usage: `commands.send_to_charts(ChartRoute::marked::<Enemy>(), |root| Alert(root))`.
+    fn send_to_charts<E>(&mut self, route: ChartRoute, make: impl Fn(Entity) -> E + Send + Sync + 'static)
+    where
+        E: EntityEvent,
+        for<'a> <E as Event>::Trigger<'a>: Default;
+
+    /// Like `send_to_charts`, but machines are selected and sent to after `delay`.
+    fn send_to_charts_after<E>(&mut self, delay: Duration, route: ChartRoute, make: impl Fn(Entity) -> E + Send + Sync + 'static)
+    where
+        E: EntityEvent,
+        for<'a> <E as Event>::Trigger<'a>: Default;
+
+    /// Like `send_to_charts_after`, but the delay follows the clock of the machine rooted at `clock`.
+    fn send_to_charts_after_on<E>(&mut self, clock: Entity, delay: Duration, route: ChartRoute, make: impl Fn(Entity) -> E + Send + Sync + 'static)
+    where
+        E: EntityEvent,
+        for<'a> <E as Event>::Trigger<'a>: Default;
+}
+
+fn send_and_report<E>(route: ChartRoute, make: impl Fn(Entity) -> E + Send + Sync + 'static) -> impl FnOnce(&mut World) + Send + Sync
+where
+    E: EntityEvent,
+    for<'a> <E as Event>::Trigger<'a>: Default,
+{
+    move |world: &mut World| {
+        let report = send_to_charts(world, &route, make);
+        world.trigger(ChartSendReport { event: std::any::type_name::<E>(), report });
+    }
+}
+
+impl<'w, 's> ChartSendCommandsExt for Commands<'w, 's> {
+    fn send_to_charts<E>(&mut self, route: ChartRoute, make: impl Fn(Entity) -> E + Send + Sync + 'static)
+    where
+        E: EntityEvent,
+        for<'a> <E as Event>::Trigger<'a>: Default,
+    {
+        self.queue(send_and_report(route, make));
+    }
+
+    fn send_to_charts_after<E>(&mut self, delay: Duration, route: ChartRoute, make: impl Fn(Entity) -> E + Send + Sync + 'static)
+    where
+        E: EntityEvent,
+        for<'a> <E as Event>::Trigger<'a>: Default,
+    {
+        self.spawn(DelayedSend {
+            timer: Timer::new(delay, TimerMode::Once),
+            clock: None,
+            send: Some(Box::new(send_and_report(route, make))),
+        });
+    }
+
+    fn send_to_charts_after_on<E>(&mut self, clock: Entity, delay: Duration, route: ChartRoute, make: impl Fn(Entity) -> E + Send + Sync + 'static)
+    where
+        E: EntityEvent,
+        for<'a> <E as Event>::Trigger<'a>: Default,
+    {
+        self.spawn(DelayedSend {
+            timer: Timer::new(delay, TimerMode::Once),
+            clock: Some(clock),
+            send: Some(Box::new(send_and_report(route, make))),
+        });
+    }
+}
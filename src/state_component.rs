@@ -1,4 +1,7 @@
-use bevy::{ecs::component::Mutable, prelude::*};
+use std::{any::TypeId, marker::PhantomData};
+
+use bevy::{ecs::{component::Mutable, relationship::RelationshipTarget}, prelude::*};
+use bevy::platform::collections::{HashMap, HashSet};
 
 use crate::{EnterState, ExitState, StateChildOf};
 
@@ -13,7 +16,172 @@ pub struct StateComponent<T: Component>(pub T);
 #[derive(Component)]
 pub struct StateInactiveComponent<T: Component + Clone>(pub T);
 
-/// A generic system that adds a component `T` to the state machine's root entity
+/// Like `StateComponent`, but inserts a whole bundle on enter and removes it on exit.
+#[derive(Component)]
+pub struct StateBundle<B: Bundle + Clone>(pub B);
+
+type ResolveTarget = Box<dyn Fn(&World, Entity, Entity) -> Option<Entity> + Send + Sync>;
+
+/// Chooses the entity a state's `StateComponent`s, `StateInactiveComponent`s, `StateBundle`s
+/// and `StateReflectComponents` apply to. Without one they apply to the machine root.
+/// The target is resolved when the state is entered and kept until it exits.
+#[derive(Component)]
+pub struct StateComponentTarget(ResolveTarget);
+
+impl StateComponentTarget {
+    /// The machine root.
+    pub fn root() -> Self {
+        Self(Box::new(|_: &World, root, _| Some(root)))
+    }
+
+    /// The state entity itself.
+    pub fn state() -> Self {
+        Self(Box::new(|_: &World, _, state| Some(state)))
+    }
+
+    /// The entity owning the machine: the root's `ChildOf` parent, or the root if it has none.
+    pub fn owner() -> Self {
+        Self(Box::new(|world: &World, root, _| {
+            Some(world.get::<ChildOf>(root).map_or(root, ChildOf::parent))
+        }))
+    }
+
+    /// A fixed entity.
+    pub fn entity(entity: Entity) -> Self {
+        Self(Box::new(move |_: &World, _, _| Some(entity)))
+    }
+
+    /// The first entity related to the root through `R` that carries marker `M`,
+    /// e.g. `related::<Children, Weapon>()`.
+    pub fn related<R: RelationshipTarget, M: Component>() -> Self {
+        Self(Box::new(|world: &World, root, _| {
+            world.get::<R>(root)?.iter().find(|&e| world.get::<M>(e).is_some())
+        }))
+    }
+
+    /// Any other rule, given the world, the machine root and the state.
+    pub fn custom(resolve: impl Fn(&World, Entity, Entity) -> Option<Entity> + Send + Sync + 'static) -> Self {
+        Self(Box::new(resolve))
+    }
+
+    pub fn resolve(&self, world: &World, root: Entity, state: Entity) -> Option<Entity> {
+        (self.0)(world, root, state).filter(|&e| world.get_entity(e).is_ok())
+    }
+}
+
+/// On a state: the entity its components were applied to when it was entered.
+#[derive(Component)]
+struct AppliedTarget(Entity);
+
+/// The state's default target, falling back to the root when it has no `StateComponentTarget`.
+fn resolve_target(world: &World, root: Entity, state: Entity) -> Option<Entity> {
+    match world.get::<StateComponentTarget>(state) {
+        Some(target) => target.resolve(world, root, state),
+        None => Some(root),
+    }
+}
+
+/// Resolve the state's target on enter and remember it for the matching exit.
+fn enter_target(world: &mut World, root: Entity, state: Entity) -> Option<Entity> {
+    let target = resolve_target(world, root, state);
+    let Ok(mut state) = world.get_entity_mut(state) else { return target; };
+    match target {
+        Some(target) => { state.insert(AppliedTarget(target)); }
+        None => { state.remove::<AppliedTarget>(); }
+    }
+    target
+}
+
+fn exit_target(world: &World, root: Entity, state: Entity) -> Entity {
+    world.get::<AppliedTarget>(state).map_or(root, |applied| applied.0)
+}
+
+/// On a target entity: the active states carrying `C` (a `StateComponent<T>`,
+/// `StateInactiveComponent<T>` or `StateBundle<B>`) applied to it, in entry order,
+/// so overlapping states layer correctly.
+#[derive(Component)]
+pub struct ActiveProviders<C: Component> {
+    providers: Vec<Entity>,
+    _marker: PhantomData<C>,
+}
+
+impl<C: Component> Default for ActiveProviders<C> {
+    fn default() -> Self { Self { providers: Vec::new(), _marker: PhantomData } }
+}
+
+impl<C: Component> ActiveProviders<C> {
+    /// Oldest first; the last one is the most recently entered.
+    pub fn providers(&self) -> &[Entity] { &self.providers }
+}
+
+/// Record `state` as the newest provider of `C` on `target`.
+fn push_provider<C: Component>(world: &mut World, target: Entity, state: Entity) {
+    let Ok(mut target) = world.get_entity_mut(target) else { return; };
+    let mut tracker = target.entry::<ActiveProviders<C>>().or_default();
+    let mut tracker = tracker.get_mut();
+    tracker.providers.retain(|&e| e != state);
+    tracker.providers.push(state);
+}
+
+/// Forget `state` (and despawned providers) as a provider of `C` on `target`.
+/// Returns the remaining providers and whether `state` was the newest live one,
+/// or `None` if `state` wasn't a provider.
+fn pop_provider<C: Component>(world: &mut World, target: Entity, state: Entity) -> Option<(Vec<Entity>, bool)> {
+    let tracked = world.get::<ActiveProviders<C>>(target)?;
+    if !tracked.providers.contains(&state) { return None; }
+    let live: Vec<Entity> = tracked.providers.iter()
+        .copied()
+        .filter(|&e| e == state || world.get_entity(e).is_ok())
+        .collect();
+    let was_top = live.last() == Some(&state);
+    let remaining: Vec<Entity> = live.into_iter().filter(|&e| e != state).collect();
+    world.get_mut::<ActiveProviders<C>>(target)?.providers = remaining.clone();
+    Some((remaining, was_top))
+}
+
+/// Apply provider `C`'s value to the state's target.
+fn provide<C: Component, B: Bundle>(world: &mut World, root: Entity, state: Entity, value: B) {
+    let Some(target) = enter_target(world, root, state) else { return; };
+    push_provider::<C>(world, target, state);
+    if let Ok(mut target) = world.get_entity_mut(target) {
+        target.insert(value);
+    }
+}
+
+/// Withdraw provider `C`. If it was the newest provider the target falls back to the newest
+/// remaining provider's value; otherwise the current value, runtime changes included, is kept.
+fn withdraw<C: Component, B: Bundle>(world: &mut World, root: Entity, state: Entity, value_of: impl Fn(&C) -> B) {
+    let target = exit_target(world, root, state);
+    let Some((remaining, was_top)) = pop_provider::<C>(world, target, state) else { return; };
+    if !was_top { return; }
+    let value = remaining.last().and_then(|&top| world.get::<C>(top)).map(value_of);
+    let Ok(mut target) = world.get_entity_mut(target) else { return; };
+    match value {
+        Some(value) => { target.insert(value); }
+        None => { target.remove::<(B, ActiveProviders<C>)>(); }
+    }
+}
+
+/// Suppress `B` on the state's target while provider `C` is active.
+fn suppress<C: Component, B: Bundle>(world: &mut World, root: Entity, state: Entity) {
+    let Some(target) = enter_target(world, root, state) else { return; };
+    push_provider::<C>(world, target, state);
+    if let Ok(mut target) = world.get_entity_mut(target) {
+        target.remove::<B>();
+    }
+}
+
+/// Lift a suppression; `value` is restored once no suppressor remains.
+fn unsuppress<C: Component, B: Bundle>(world: &mut World, root: Entity, state: Entity, value: B) {
+    let target = exit_target(world, root, state);
+    let Some((remaining, _)) = pop_provider::<C>(world, target, state) else { return; };
+    if !remaining.is_empty() { return; }
+    let Ok(mut target) = world.get_entity_mut(target) else { return; };
+    target.remove::<ActiveProviders<C>>();
+    target.insert(value);
+}
+
+/// A generic system that adds a component `T` to the state's target (the machine root by default)
 /// when a state with `StateComponent<T>` is entered.
 pub fn state_component_enter<T: Component<Mutability = Mutable> + Clone>(
     enter_state: On<EnterState>,
@@ -29,31 +197,31 @@ pub fn state_component_enter<T: Component<Mutability = Mutable> + Clone>(
     let root_entity = q_child_of.root_ancestor(entered_state);
 
     if root_entity != entered_state {
-        commands.entity(root_entity).insert(insert_component.0.clone());
+        let value = insert_component.0.clone();
+        commands.queue(move |world: &mut World| {
+            provide::<StateComponent<T>, T>(world, root_entity, entered_state, value);
+        });
     }
 }
 
-/// A generic system that removes a component `T` from the state machine's root entity
-/// when a state with `StateComponent<T>` is exited.
-pub fn state_component_exit<T: Component>(
+/// A generic system that removes a component `T` from the state's target
+/// when the last active state with `StateComponent<T>` is exited. If other providers remain,
+/// `T` takes the value of the most recently entered one.
+pub fn state_component_exit<T: Component<Mutability = Mutable> + Clone>(
     exit_state: On<ExitState>,
-    q_state_component: Query<&StateComponent<T>>,
-    q_child_of: Query<&StateChildOf>,
+    q_state_component: Query<(), With<StateComponent<T>>>,
     mut commands: Commands,
 ) {
     let exited_state = exit_state.target;
-    if !q_state_component.contains(exited_state) {
-        return;
-    };
+    let root_entity = exit_state.state_machine;
+    if root_entity == exited_state || !q_state_component.contains(exited_state) { return; }
 
-    let root_entity = q_child_of.root_ancestor(exited_state);
-
-    if root_entity != exited_state {
-        commands.entity(root_entity).remove::<T>();
-    }
+    commands.queue(move |world: &mut World| {
+        withdraw::<StateComponent<T>, T>(world, root_entity, exited_state, |c| c.0.clone());
+    });
 }
 
-/// A generic system that removes a component `T` from the state machine's root entity
+/// A generic system that removes a component `T` from the state's target
 /// when a state with `StateInactiveComponent<T>` is entered.
 pub fn state_inactive_component_enter<T: Component + Clone>(
     enter_state: On<EnterState>,
@@ -69,28 +237,215 @@ pub fn state_inactive_component_enter<T: Component + Clone>(
     let root_entity = q_child_of.root_ancestor(entered_state);
 
     if root_entity != entered_state {
-        commands.entity(root_entity).remove::<T>();
+        commands.queue(move |world: &mut World| {
+            suppress::<StateInactiveComponent<T>, T>(world, root_entity, entered_state);
+        });
     }
 }
 
-/// A generic system that restores a component `T` to the state machine's root entity
-/// when a state with `StateInactiveComponent<T>` is exited, using the stored clone.
+/// A generic system that restores a component `T` to the state's target
+/// when the last active state with `StateInactiveComponent<T>` is exited, using its stored clone.
 pub fn state_inactive_component_exit<T: Component + Clone>(
     exit_state: On<ExitState>,
     q_state_inactive_component: Query<&StateInactiveComponent<T>>,
+    mut commands: Commands,
+) {
+    let exited_state = exit_state.target;
+    let root_entity = exit_state.state_machine;
+    if root_entity == exited_state { return; }
+    let Ok(StateInactiveComponent(value)) = q_state_inactive_component.get(exited_state) else { return; };
+    let value = value.clone();
+
+    commands.queue(move |world: &mut World| {
+        unsuppress::<StateInactiveComponent<T>, T>(world, root_entity, exited_state, value);
+    });
+}
+
+/// Inserts bundle `B` on the state's target when a state with `StateBundle<B>` is entered.
+pub fn state_bundle_enter<B: Bundle + Clone>(
+    enter_state: On<EnterState>,
+    q_state_bundle: Query<&StateBundle<B>>,
     q_child_of: Query<&StateChildOf>,
     mut commands: Commands,
+) {
+    let entered_state = enter_state.target;
+    let Ok(bundle) = q_state_bundle.get(entered_state) else { return; };
+    let root_entity = q_child_of.root_ancestor(entered_state);
+    if root_entity == entered_state { return; }
+
+    let value = bundle.0.clone();
+    commands.queue(move |world: &mut World| {
+        provide::<StateBundle<B>, B>(world, root_entity, entered_state, value);
+    });
+}
+
+/// Removes bundle `B` from the state's target when its last provider exits,
+/// or restores the most recently entered remaining provider's bundle.
+pub fn state_bundle_exit<B: Bundle + Clone>(
+    exit_state: On<ExitState>,
+    q_state_bundle: Query<(), With<StateBundle<B>>>,
+    mut commands: Commands,
 ) {
     let exited_state = exit_state.target;
-    let Ok(remove_component) = q_state_inactive_component.get(exited_state) else {
-        return;
-    };
+    let root_entity = exit_state.state_machine;
+    if root_entity == exited_state || !q_state_bundle.contains(exited_state) { return; }
+
+    commands.queue(move |world: &mut World| {
+        withdraw::<StateBundle<B>, B>(world, root_entity, exited_state, |b| b.0.clone());
+    });
+}
+
+struct ReflectEntry {
+    target: Option<StateComponentTarget>,
+    value: Box<dyn PartialReflect>,
+}
 
-    let root_entity = q_child_of.root_ancestor(exited_state);
+/// Reflected components a state applies while active, for sets only known at runtime
+/// (e.g. loaded from assets). Every type must be registered with `#[reflect(Component)]`.
+/// Entries go on the state's target unless they name their own.
+#[derive(Component, Default)]
+pub struct StateReflectComponents {
+    entries: Vec<ReflectEntry>,
+}
+
+impl StateReflectComponents {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a component applied to the state's target.
+    pub fn with(mut self, value: impl Reflect) -> Self {
+        self.push(None, Box::new(value));
+        self
+    }
 
-    if root_entity != exited_state {
-        commands.entity(root_entity).insert(remove_component.0.clone());
+    /// Add a component applied to its own `target`.
+    pub fn with_on(mut self, target: StateComponentTarget, value: impl Reflect) -> Self {
+        self.push(Some(target), Box::new(value));
+        self
     }
+
+    pub fn push(&mut self, target: Option<StateComponentTarget>, value: Box<dyn PartialReflect>) {
+        self.entries.push(ReflectEntry { target, value });
+    }
+}
+
+/// On a state: `(entry index, target, component type)` for each applied reflected component.
+#[derive(Component)]
+pub(crate) struct AppliedReflectComponents(Vec<(usize, Entity, TypeId)>);
+
+/// On a target entity: the active providers of each reflected component type, in entry order.
+#[derive(Component, Default)]
+struct ActiveReflectProviders(HashMap<TypeId, Vec<Entity>>);
+
+/// A clone of `provider`'s entry for `type_id` on `target`, if it applied one.
+fn reflect_value_of(world: &World, provider: Entity, target: Entity, type_id: TypeId) -> Option<Box<dyn PartialReflect>> {
+    let applied = world.get::<AppliedReflectComponents>(provider)?;
+    let set = world.get::<StateReflectComponents>(provider)?;
+    let &(index, _, _) = applied.0.iter().find(|&&(_, t, id)| t == target && id == type_id)?;
+    Some(set.entries.get(index)?.value.to_dynamic())
+}
+
+fn apply_reflect_components(world: &mut World, root: Entity, state: Entity) {
+    let Some(set) = world.get::<StateReflectComponents>(state) else { return; };
+    let default_target = resolve_target(world, root, state);
+    let mut applied = Vec::new();
+    let mut values = Vec::new();
+    for (index, entry) in set.entries.iter().enumerate() {
+        let target = match &entry.target {
+            Some(target) => target.resolve(world, root, state),
+            None => default_target,
+        };
+        let Some(target) = target else { continue; };
+        let Some(info) = entry.value.get_represented_type_info() else { continue; };
+        applied.push((index, target, info.type_id()));
+        values.push(entry.value.to_dynamic());
+    }
+    let Ok(mut state_entity) = world.get_entity_mut(state) else { return; };
+    state_entity.insert(AppliedReflectComponents(applied.clone()));
+
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    for ((_, target, type_id), value) in applied.into_iter().zip(values) {
+        let Some(reflect_component) = registry.get_type_data::<ReflectComponent>(type_id) else {
+            warn!("StateReflectComponents on {state}: {} is not registered with #[reflect(Component)]", value.reflect_type_path());
+            continue;
+        };
+        let Ok(mut target) = world.get_entity_mut(target) else { continue; };
+        let mut tracker = target.entry::<ActiveReflectProviders>().or_default();
+        let mut tracker = tracker.get_mut();
+        let providers = tracker.0.entry(type_id).or_default();
+        providers.retain(|&e| e != state);
+        providers.push(state);
+        reflect_component.insert(&mut target, value.as_partial_reflect(), &registry);
+    }
+}
+
+fn withdraw_reflect_components(world: &mut World, state: Entity) {
+    let Some(applied) = world.get_entity_mut(state).ok().and_then(|mut s| s.take::<AppliedReflectComponents>()) else { return; };
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    for (_, target, type_id) in applied.0 {
+        let Some(reflect_component) = registry.get_type_data::<ReflectComponent>(type_id) else { continue; };
+        let Some(tracker) = world.get::<ActiveReflectProviders>(target) else { continue; };
+        let Some(providers) = tracker.0.get(&type_id) else { continue; };
+        if !providers.contains(&state) { continue; }
+        let live: Vec<Entity> = providers.iter()
+            .copied()
+            .filter(|&e| e == state || world.get_entity(e).is_ok())
+            .collect();
+        let was_top = live.last() == Some(&state);
+        let remaining: Vec<Entity> = live.into_iter().filter(|&e| e != state).collect();
+        let value = remaining.last().and_then(|&top| reflect_value_of(world, top, target, type_id));
+
+        let Ok(mut target_entity) = world.get_entity_mut(target) else { continue; };
+        let Some(mut tracker) = target_entity.get_mut::<ActiveReflectProviders>() else { continue; };
+        if remaining.is_empty() {
+            tracker.0.remove(&type_id);
+        } else {
+            tracker.0.insert(type_id, remaining);
+        }
+        if tracker.0.is_empty() {
+            target_entity.remove::<ActiveReflectProviders>();
+        }
+        // An older provider leaving keeps the newest one's current value
+        if !was_top { continue; }
+        match value {
+            Some(value) => reflect_component.insert(&mut target_entity, value.as_partial_reflect(), &registry),
+            None => reflect_component.remove(&mut target_entity),
+        }
+    }
+}
+
+/// Applies a state's `StateReflectComponents` when it is entered.
+pub(crate) fn state_reflect_components_enter(
+    enter_state: On<EnterState>,
+    q_sets: Query<(), With<StateReflectComponents>>,
+    q_child_of: Query<&StateChildOf>,
+    mut commands: Commands,
+) {
+    let entered_state = enter_state.target;
+    if !q_sets.contains(entered_state) { return; }
+    let root_entity = q_child_of.root_ancestor(entered_state);
+    if root_entity == entered_state { return; }
+
+    commands.queue(move |world: &mut World| {
+        apply_reflect_components(world, root_entity, entered_state);
+    });
+}
+
+/// Withdraws a state's `StateReflectComponents` when it is exited.
+pub(crate) fn state_reflect_components_exit(
+    exit_state: On<ExitState>,
+    q_applied: Query<(), With<AppliedReflectComponents>>,
+    mut commands: Commands,
+) {
+    let exited_state = exit_state.target;
+    if exit_state.state_machine == exited_state || !q_applied.contains(exited_state) { return; }
+
+    commands.queue(move |world: &mut World| {
+        withdraw_reflect_components(world, exited_state);
+    });
 }
 
 /// Helper trait to add state component observers to an App.
@@ -102,20 +457,109 @@ pub trait StateComponentAppExt {
     /// Registers both enter and exit observers for `StateInactiveComponent<T>`.
     /// This is a convenience method to avoid having to register both observers manually.
     fn add_state_inactive_component<T: Component<Mutability = Mutable> + Clone>(&mut self) -> &mut Self;
+
+    /// Registers both enter and exit observers for `StateBundle<B>`.
+    fn add_state_bundle<B: Bundle + Clone>(&mut self) -> &mut Self;
 }
 
 impl StateComponentAppExt for App {
     fn add_state_component<T: Component<Mutability = Mutable> + Clone>(&mut self) -> &mut Self {
-        self.add_observer(state_component_enter::<T>)
-            .add_observer(state_component_exit::<T>)
+        register_state_component::<T>(self);
+        self
     }
     
     fn add_state_inactive_component<T: Component<Mutability = Mutable> + Clone>(&mut self) -> &mut Self {
-        self.add_observer(state_inactive_component_enter::<T>)
-            .add_observer(state_inactive_component_exit::<T>)
+        register_state_inactive_component::<T>(self);
+        self
+    }
+
+    fn add_state_bundle<B: Bundle + Clone>(&mut self) -> &mut Self {
+        register_state_bundle::<B>(self);
+        self
     }
 }
 
+/// Internal resource to dedupe per-type state component installation.
+#[derive(Resource, Default)]
+pub struct InstalledStateComponents(pub HashSet<TypeId>);
+
+/// Installer record collected via `inventory` for auto-registration of state component types.
+/// Submitted by `register_state_component!`, `register_state_inactive_component!`, `register_state_bundle!`,
+/// `register_state_resource!` and `register_resettable!`.
+pub struct StateComponentInstaller {
+    pub install: fn(&mut App),
+}
+
+inventory::collect!(StateComponentInstaller);
+
+/// Returns true the first time it is called for `K` on this app.
+pub(crate) fn first_install<K: 'static>(app: &mut App) -> bool {
+    app.world_mut()
+        .get_resource_or_init::<InstalledStateComponents>()
+        .0
+        .insert(TypeId::of::<K>())
+}
+
+/// Adds the enter/exit observers for `StateComponent<T>` once per app.
+pub fn register_state_component<T: Component<Mutability = Mutable> + Clone>(app: &mut App) {
+    if !first_install::<StateComponent<T>>(app) { return; }
+    app.add_observer(state_component_enter::<T>)
+        .add_observer(state_component_exit::<T>);
+}
+
+/// Adds the enter/exit observers for `StateInactiveComponent<T>` once per app.
+pub fn register_state_inactive_component<T: Component<Mutability = Mutable> + Clone>(app: &mut App) {
+    if !first_install::<StateInactiveComponent<T>>(app) { return; }
+    app.add_observer(state_inactive_component_enter::<T>)
+        .add_observer(state_inactive_component_exit::<T>);
+}
+
+/// Adds the enter/exit observers for `StateBundle<B>` once per app.
+pub fn register_state_bundle<B: Bundle + Clone>(app: &mut App) {
+    if !first_install::<StateBundle<B>>(app) { return; }
+    app.add_observer(state_bundle_enter::<B>)
+        .add_observer(state_bundle_exit::<B>);
+}
+
+/// Auto-registers `StateComponent<T>` observers with `GearboxPlugin` through `inventory`.
+/// Usage: `register_state_component!(Jumping);`
+#[macro_export]
+macro_rules! register_state_component {
+    ($component:ty) => {
+        $crate::inventory::submit! {
+            $crate::state_component::StateComponentInstaller {
+                install: |app| $crate::state_component::register_state_component::<$component>(app),
+            }
+        }
+    };
+}
+
+/// Auto-registers `StateInactiveComponent<T>` observers with `GearboxPlugin` through `inventory`.
+/// Usage: `register_state_inactive_component!(CanJump);`
+#[macro_export]
+macro_rules! register_state_inactive_component {
+    ($component:ty) => {
+        $crate::inventory::submit! {
+            $crate::state_component::StateComponentInstaller {
+                install: |app| $crate::state_component::register_state_inactive_component::<$component>(app),
+            }
+        }
+    };
+}
+
+/// Auto-registers `StateBundle<B>` observers with `GearboxPlugin` through `inventory`.
+/// Usage: `register_state_bundle!((Stunned, NoInput));`
+#[macro_export]
+macro_rules! register_state_bundle {
+    ($bundle:ty) => {
+        $crate::inventory::submit! {
+            $crate::state_component::StateComponentInstaller {
+                install: |app| $crate::state_component::register_state_bundle::<$bundle>(app),
+            }
+        }
+    };
+}
+
 /// Event to reset a subtree rooted at the target entity.
 #[derive(EntityEvent, Reflect)]
 pub struct Reset { #[event_target] pub target: Entity }
@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+
+use crate::{state_component::first_install, EnterState, ExitState, StateChildOf};
+
+/// A component that when added to a state entity, will insert the contained resource `R`
+/// into the world while this state is active. States of any machine can provide the same
+/// resource; the most recently entered one wins, and the value `R` had before the first
+/// provider entered is restored once the last one exits.
+#[derive(Component)]
+pub struct StateResource<R: Resource + Clone>(pub R);
+
+/// The active states providing `R`, in entry order, and the value `R` had before them.
+#[derive(Resource)]
+pub struct StateResourceProviders<R: Resource + Clone> {
+    providers: Vec<Entity>,
+    previous: Option<R>,
+}
+
+impl<R: Resource + Clone> StateResourceProviders<R> {
+    /// Oldest first; the last one is the most recently entered.
+    pub fn providers(&self) -> &[Entity] { &self.providers }
+
+    /// The value restored once every provider has exited.
+    pub fn previous(&self) -> Option<&R> { self.previous.as_ref() }
+}
+
+/// Inserts `R` when a state with `StateResource<R>` is entered.
+pub fn state_resource_enter<R: Resource + Clone>(
+    enter_state: On<EnterState>,
+    q_state_resource: Query<&StateResource<R>>,
+    q_child_of: Query<&StateChildOf>,
+    mut commands: Commands,
+) {
+    let entered_state = enter_state.target;
+    let Ok(state_resource) = q_state_resource.get(entered_state) else { return; };
+    if q_child_of.root_ancestor(entered_state) == entered_state { return; }
+
+    let value = state_resource.0.clone();
+    commands.queue(move |world: &mut World| {
+        if !world.contains_resource::<StateResourceProviders<R>>() {
+            let previous = world.remove_resource::<R>();
+            world.insert_resource(StateResourceProviders { providers: Vec::new(), previous });
+        }
+        let mut tracker = world.resource_mut::<StateResourceProviders<R>>();
+        tracker.providers.retain(|&e| e != entered_state);
+        tracker.providers.push(entered_state);
+        world.insert_resource(value);
+    });
+}
+
+/// Falls back to the newest remaining provider's `R` when the newest providing state exits,
+/// or restores the previous value (removing `R` if there was none) after the last one.
+/// An older provider exiting leaves `R`, runtime changes included, untouched.
+pub fn state_resource_exit<R: Resource + Clone>(
+    exit_state: On<ExitState>,
+    mut commands: Commands,
+) {
+    let exited_state = exit_state.target;
+    if exit_state.state_machine == exited_state { return; }
+
+    commands.queue(move |world: &mut World| {
+        let Some(tracker) = world.get_resource::<StateResourceProviders<R>>() else { return; };
+        if !tracker.providers.contains(&exited_state) { return; }
+        let live: Vec<Entity> = tracker.providers.iter()
+            .copied()
+            .filter(|&e| e == exited_state || world.get_entity(e).is_ok())
+            .collect();
+        let was_top = live.last() == Some(&exited_state);
+        let remaining: Vec<Entity> = live.into_iter().filter(|&e| e != exited_state).collect();
+        // An older provider leaving keeps the newest one's current value
+        if !was_top && !remaining.is_empty() {
+            world.resource_mut::<StateResourceProviders<R>>().providers = remaining;
+            return;
+        }
+        let value = remaining.last().and_then(|&top| world.get::<StateResource<R>>(top)).map(|r| r.0.clone());
+        match value {
+            Some(value) => {
+                world.resource_mut::<StateResourceProviders<R>>().providers = remaining;
+                world.insert_resource(value);
+            }
+            None => {
+                let previous = world.remove_resource::<StateResourceProviders<R>>().and_then(|t| t.previous);
+                match previous {
+                    Some(previous) => world.insert_resource(previous),
+                    None => { world.remove_resource::<R>(); }
+                }
+            }
+        }
+    });
+}
+
+/// Helper trait to add state resource observers to an App.
+pub trait StateResourceAppExt {
+    /// Registers both enter and exit observers for `StateResource<R>`.
+    fn add_state_resource<R: Resource + Clone>(&mut self) -> &mut Self;
+}
+
+impl StateResourceAppExt for App {
+    fn add_state_resource<R: Resource + Clone>(&mut self) -> &mut Self {
+        register_state_resource::<R>(self);
+        self
+    }
+}
+
+/// Adds the enter/exit observers for `StateResource<R>` once per app.
+pub fn register_state_resource<R: Resource + Clone>(app: &mut App) {
+    if !first_install::<StateResource<R>>(app) { return; }
+    app.add_observer(state_resource_enter::<R>)
+        .add_observer(state_resource_exit::<R>);
+}
+
+/// Auto-registers `StateResource<R>` observers with `GearboxPlugin` through `inventory`.
+/// Usage: `register_state_resource!(MatchRules);`
+#[macro_export]
+macro_rules! register_state_resource {
+    ($resource:ty) => {
+        $crate::inventory::submit! {
+            $crate::state_component::StateComponentInstaller {
+                install: |app| $crate::state_resource::register_state_resource::<$resource>(app),
+            }
+        }
+    };
+}
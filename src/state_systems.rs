@@ -0,0 +1,139 @@
+use std::marker::PhantomData;
+
+use bevy::{ecs::{schedule::ScheduleLabel, system::SystemId}, platform::collections::HashMap, prelude::*};
+
+use crate::{active::Active, EnterState, ExitState, StateChildOf};
+
+/// Input passed to per-state systems: the machine root and the state carrying the marker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StateContext {
+    pub root: Entity,
+    pub state: Entity,
+}
+
+type StateSystemId = SystemId<In<StateContext>>;
+
+/// One system or a tuple of systems taking `In<StateContext>`.
+pub trait IntoStateSystems<Marker> {
+    fn register(self, world: &mut World) -> Vec<StateSystemId>;
+}
+
+macro_rules! impl_into_state_systems {
+    ($(($system:ident, $marker:ident)),+) => {
+        impl<$($system, $marker),+> IntoStateSystems<($($marker,)+)> for ($($system,)+)
+        where
+            $($system: IntoSystem<In<StateContext>, (), $marker> + 'static),+
+        {
+            #[allow(non_snake_case)]
+            fn register(self, world: &mut World) -> Vec<StateSystemId> {
+                let ($($system,)+) = self;
+                vec![$(world.register_system($system)),+]
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+pub struct SingleStateSystem<M>(PhantomData<M>);
+
+impl<S, M> IntoStateSystems<SingleStateSystem<M>> for S
+where
+    S: IntoSystem<In<StateContext>, (), M> + 'static,
+{
+    fn register(self, world: &mut World) -> Vec<StateSystemId> {
+        vec![world.register_system(self)]
+    }
+}
+
+impl_into_state_systems!((S0, M0));
+impl_into_state_systems!((S0, M0), (S1, M1));
+impl_into_state_systems!((S0, M0), (S1, M1), (S2, M2));
+impl_into_state_systems!((S0, M0), (S1, M1), (S2, M2), (S3, M3));
+impl_into_state_systems!((S0, M0), (S1, M1), (S2, M2), (S3, M3), (S4, M4));
+impl_into_state_systems!((S0, M0), (S1, M1), (S2, M2), (S3, M3), (S4, M4), (S5, M5));
+impl_into_state_systems!((S0, M0), (S1, M1), (S2, M2), (S3, M3), (S4, M4), (S5, M5), (S6, M6));
+impl_into_state_systems!((S0, M0), (S1, M1), (S2, M2), (S3, M3), (S4, M4), (S5, M5), (S6, M6), (S7, M7));
+
+/// Runs `systems` once for every machine with an active state carrying `M`. When several
+/// active states carry it (parallel regions, nested states), the deepest one is passed,
+/// ties broken by entity order.
+fn run_state_systems<M: Component>(world: &mut World, systems: &[StateSystemId]) {
+    let mut q_states = world.query_filtered::<Entity, (With<M>, With<Active>)>();
+    let mut q_child_of = world.query::<&StateChildOf>();
+    let q_child_of = q_child_of.query(world);
+    let mut per_machine: HashMap<Entity, (usize, Entity)> = HashMap::new();
+    for state in q_states.iter(world) {
+        let depth = q_child_of.iter_ancestors(state).count();
+        let root = q_child_of.root_ancestor(state);
+        let candidate = (depth, state);
+        per_machine.entry(root)
+            .and_modify(|best| {
+                if candidate.0 > best.0 || (candidate.0 == best.0 && candidate.1 < best.1) { *best = candidate; }
+            })
+            .or_insert(candidate);
+    }
+    let mut contexts: Vec<StateContext> = per_machine.into_iter()
+        .map(|(root, (_, state))| StateContext { root, state })
+        .collect();
+    contexts.sort_by_key(|context| context.root);
+    for context in contexts {
+        for &system in systems {
+            if let Err(error) = world.run_system_with(system, context) {
+                warn!("state system for {} failed: {error}", context.state);
+            }
+        }
+    }
+}
+
+/// Helper trait to add systems keyed by state marker components to an App.
+pub trait StateSystemsAppExt {
+    /// Runs `systems` in `schedule` once per machine currently in a state carrying `M`,
+    /// with that machine's root and its deepest such state as input. `marker` only names
+    /// the component type.
+    fn add_state_systems<M: Component, Marker>(
+        &mut self,
+        schedule: impl ScheduleLabel,
+        marker: M,
+        systems: impl IntoStateSystems<Marker>,
+    ) -> &mut Self;
+
+    /// Runs `systems` once each time a state carrying `M` is entered.
+    fn add_state_enter_systems<M: Component, Marker>(&mut self, marker: M, systems: impl IntoStateSystems<Marker>) -> &mut Self;
+
+    /// Runs `systems` once each time a state carrying `M` is exited.
+    fn add_state_exit_systems<M: Component, Marker>(&mut self, marker: M, systems: impl IntoStateSystems<Marker>) -> &mut Self;
+}
+
+impl StateSystemsAppExt for App {
+    fn add_state_systems<M: Component, Marker>(
+        &mut self,
+        schedule: impl ScheduleLabel,
+        _marker: M,
+        systems: impl IntoStateSystems<Marker>,
+    ) -> &mut Self {
+        let systems = systems.register(self.world_mut());
+        self.add_systems(schedule, move |world: &mut World| run_state_systems::<M>(world, &systems))
+    }
+
+    fn add_state_enter_systems<M: Component, Marker>(&mut self, _marker: M, systems: impl IntoStateSystems<Marker>) -> &mut Self {
+        let systems = systems.register(self.world_mut());
+        self.add_observer(move |enter_state: On<EnterState>, q_marked: Query<(), With<M>>, mut commands: Commands| {
+            if !q_marked.contains(enter_state.target) { return; }
+            let context = StateContext { root: enter_state.state_machine, state: enter_state.target };
+            for &system in &systems {
+                commands.run_system_with(system, context);
+            }
+        })
+    }
+
+    fn add_state_exit_systems<M: Component, Marker>(&mut self, _marker: M, systems: impl IntoStateSystems<Marker>) -> &mut Self {
+        let systems = systems.register(self.world_mut());
+        self.add_observer(move |exit_state: On<ExitState>, q_marked: Query<(), With<M>>, mut commands: Commands| {
+            if !q_marked.contains(exit_state.target) { return; }
+            let context = StateContext { root: exit_state.state_machine, state: exit_state.target };
+            for &system in &systems {
+                commands.run_system_with(system, context);
+            }
+        })
+    }
+}
@@ -1,7 +1,9 @@
+use std::collections::VecDeque;
 use std::marker::PhantomData;
 use std::time::Duration;
 
 use bevy::prelude::*;
+use bevy::ecs::{intern::Interned, schedule::ScheduleLabel, system::SystemParam};
 use bevy::platform::collections::HashSet;
 use std::any::TypeId;
 
@@ -76,11 +78,329 @@ impl After {
     pub fn new(duration: Duration) -> Self { Self { duration } }
 
     pub fn from_f32(duration: f32) -> Self { Self { duration: Duration::from_secs_f32(duration) } }
+
+    /// Delay sampled uniformly between `min` and `max` seconds each time the timer starts,
+    /// from the machine's `ChartRng`.
+    ///
+    /// # Panics
+    /// If either bound is negative or not finite, or `min > max`.
+    pub fn between(min: f32, max: f32) -> (Self, DelaySource) {
+        assert!(
+            min.is_finite() && max.is_finite() && min >= 0.0 && min <= max,
+            "After::between expects finite bounds with 0 <= min <= max, got min = {min}, max = {max}",
+        );
+        let (min, max) = (Duration::from_secs_f32(min), Duration::from_secs_f32(max));
+        (Self::new(min), DelaySource::Between { min, max })
+    }
+
+    /// Delay read in seconds from `FloatParam<P>` (through the edge's `ParamScope`) each time
+    /// the timer starts. Uses `fallback` while the parameter is missing.
+    pub fn from_param<P: Send + Sync + 'static>(fallback: Duration) -> (Self, DelaySource) {
+        (Self::new(fallback), DelaySource::Param(crate::parameter::param_delay::<P>))
+    }
+}
+
+/// How an After edge picks its delay each time its timer starts. Without it, `After::duration` is used.
+#[derive(Component, Clone, Copy, Debug)]
+pub enum DelaySource {
+    /// Uniformly sampled between `min` and `max` from the machine's `ChartRng`.
+    Between { min: Duration, max: Duration },
+    /// Read for the given edge; see `After::from_param`.
+    Param(fn(&World, Entity) -> Option<Duration>),
+}
+
+/// Seedable per-machine random number generator (SplitMix64), placed on the machine root.
+/// Inserted on first use, seeded from the root entity, if not provided.
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component)]
+pub struct ChartRng {
+    state: u64,
+}
+
+impl ChartRng {
+    #[inline]
+    pub fn from_seed(seed: u64) -> Self { Self { state: seed } }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform between `min` and `max`.
+    pub fn duration_between(&mut self, min: Duration, max: Duration) -> Duration {
+        if max <= min { return min; }
+        min + (max - min).mul_f32(self.next_f32())
+    }
+}
+
+/// Starts the After timer of `edge`, resolving its `DelaySource` if any.
+fn arm_after_timer(commands: &mut Commands, edge: Entity, after: &After) {
+    commands.entity(edge).insert(EdgeTimer(Timer::new(after.duration, TimerMode::Once)));
+    queue_delay_resolution(commands, edge);
+}
+
+/// Queues re-timing an already (re)started After timer from the edge's `DelaySource`.
+fn queue_delay_resolution(commands: &mut Commands, edge: Entity) {
+    commands.queue(move |world: &mut World| {
+        let Some(&delay) = world.get::<DelaySource>(edge) else { return; };
+        let duration = match delay {
+            DelaySource::Between { min, max } => {
+                let Some(Source(source)) = world.get::<Source>(edge) else { return; };
+                let mut root = *source;
+                while let Some(StateChildOf(parent)) = world.get::<StateChildOf>(root) { root = *parent; }
+                let Ok(mut root) = world.get_entity_mut(root) else { return; };
+                if !root.contains::<ChartRng>() {
+                    let seed = root.id().to_bits();
+                    root.insert(ChartRng::from_seed(seed));
+                }
+                let Some(mut rng) = root.get_mut::<ChartRng>() else { return; };
+                rng.duration_between(min, max)
+            }
+            DelaySource::Param(read) => match read(world, edge) {
+                Some(duration) => duration,
+                None => return,
+            },
+        };
+        if let Some(mut timer) = world.get_mut::<EdgeTimer>(edge) {
+            timer.0.set_duration(duration);
+            timer.0.reset();
+        }
+    });
+}
+
+/// Repeating timed transition: fires every `interval` while the source is active,
+/// up to `max_repetitions` times per activation if set. Typically paired with
+/// `EdgeKind::Internal` and a `TransitionActions` observer on the edge.
+#[derive(Component, Reflect, Default, Clone, Debug)]
+#[reflect(Component, Default)]
+pub struct Every {
+    pub interval: Duration,
+    pub max_repetitions: Option<u32>,
+}
+
+impl Every {
+    #[inline]
+    pub fn new(interval: Duration) -> Self { Self { interval, max_repetitions: None } }
+
+    pub fn from_f32(interval: f32) -> Self { Self::new(Duration::from_secs_f32(interval)) }
+
+    #[inline]
+    pub fn with_max_repetitions(mut self, max_repetitions: u32) -> Self {
+        self.max_repetitions = Some(max_repetitions);
+        self
+    }
+}
+
+/// Repeating timer of an `Every` edge, present while its source is active.
+#[derive(Component)]
+pub struct EveryTimer {
+    pub timer: Timer,
+    repetitions: u32,
+}
+
+impl EveryTimer {
+    fn new(every: &Every) -> Self {
+        Self { timer: Timer::new(every.interval, TimerMode::Repeating), repetitions: 0 }
+    }
+
+    /// How many times the edge has fired during the current activation of its source.
+    #[inline]
+    pub fn repetitions(&self) -> u32 { self.repetitions }
 }
 
 #[derive(Component)]
 pub struct EdgeTimer(pub Timer);
 
+/// Clock that advances After timers.
+/// Also usable as a component on a machine root to override `TimerSettings::clock`.
+#[derive(Component, Reflect, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub enum TimerClock {
+    /// `Time<Virtual>`: pausable and scalable game time.
+    #[default]
+    Virtual,
+    /// `Time<Real>`: wall-clock time, unaffected by pausing.
+    Real,
+    /// `Time<Fixed>`: the fixed timestep; pair with `FixedUpdate` as the timer schedule.
+    Fixed,
+    /// The `ChartClock` component on the machine root.
+    Custom,
+}
+
+/// User-driven clock for machines using `TimerClock::Custom`.
+/// Set `delta` before `AfterTimerSystems` runs; it is applied on every timer tick.
+#[derive(Component, Reflect, Default, Clone, Copy, Debug)]
+#[reflect(Component, Default)]
+pub struct ChartClock {
+    pub delta: Duration,
+}
+
+/// Per-machine multiplier for After timers, placed on the machine root (e.g. slowed or hasted characters).
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[reflect(Component, Default)]
+pub struct TimeScale(pub f32);
+
+impl Default for TimeScale {
+    fn default() -> Self { Self(1.0) }
+}
+
+/// App-wide After timer configuration. Insert before adding `GearboxPlugin` to change the
+/// schedule; the clock can be changed at any time.
+#[derive(Resource, Clone, Debug)]
+pub struct TimerSettings {
+    /// Schedule that ticks After timers.
+    pub schedule: Interned<dyn ScheduleLabel>,
+    /// Default clock for machines without a `TimerClock` component.
+    pub clock: TimerClock,
+}
+
+impl Default for TimerSettings {
+    fn default() -> Self { Self { schedule: Update.intern(), clock: TimerClock::Virtual } }
+}
+
+impl TimerSettings {
+    pub fn new(schedule: impl ScheduleLabel, clock: TimerClock) -> Self {
+        Self { schedule: schedule.intern(), clock }
+    }
+}
+
+/// System set containing the After timer systems, in `TimerSettings::schedule`.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AfterTimerSystems;
+
+/// Resolves how much After timers of a given machine advance this tick.
+#[derive(SystemParam)]
+pub struct TimerDelta<'w, 's> {
+    settings: Res<'w, TimerSettings>,
+    virtual_time: Res<'w, Time<Virtual>>,
+    real_time: Res<'w, Time<Real>>,
+    fixed_time: Res<'w, Time<Fixed>>,
+    machines: Query<'w, 's, (Option<&'static TimerClock>, Option<&'static ChartClock>, Option<&'static TimeScale>)>,
+    paused: Query<'w, 's, (), With<Paused>>,
+}
+
+impl TimerDelta<'_, '_> {
+    /// Delta for the machine rooted at `root`, after its clock and `TimeScale`.
+    /// `None` while the machine is `Paused`: its timers must not tick at all.
+    pub fn for_machine(&self, root: Entity) -> Option<Duration> {
+        if self.paused.contains(root) { return None; }
+        let (clock, chart_clock, scale) = self.machines.get(root).unwrap_or((None, None, None));
+        let delta = match clock.copied().unwrap_or(self.settings.clock) {
+            TimerClock::Custom => chart_clock.map(|c| c.delta).unwrap_or_default(),
+            clock => self.clock_delta(clock),
+        };
+        Some(match scale {
+            Some(TimeScale(scale)) => delta.mul_f32(scale.max(0.0)),
+            None => delta,
+        })
+    }
+
+    /// Delta of the app-wide `TimerSettings::clock`, for timers not tied to a machine.
+    /// `TimerClock::Custom` has no `ChartClock` to read here and falls back to `Virtual`.
+    pub fn for_app(&self) -> Duration {
+        self.clock_delta(self.settings.clock)
+    }
+
+    fn clock_delta(&self, clock: TimerClock) -> Duration {
+        match clock {
+            TimerClock::Virtual | TimerClock::Custom => self.virtual_time.delta(),
+            TimerClock::Real => self.real_time.delta(),
+            TimerClock::Fixed => self.fixed_time.delta(),
+        }
+    }
+}
+
+/// Schedule that ticks After timers, initializing `TimerSettings` if needed.
+pub(crate) fn timer_schedule(app: &mut App) -> Interned<dyn ScheduleLabel> {
+    app.world_mut().get_resource_or_init::<TimerSettings>().schedule
+}
+
+/// What a running edge timer will do when it finishes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimedEdgeKind {
+    /// An `AlwaysEdge` with `After`.
+    Delayed,
+    /// An `EventEdge` with `After` holding a `PendingEvent`.
+    PendingEvent,
+    /// An `Every` edge; times refer to the current interval.
+    Every,
+}
+
+/// Snapshot of a running edge timer, as returned by `ChartTimers`.
+#[derive(Clone, Copy, Debug)]
+pub struct TimedEdge {
+    pub edge: Entity,
+    pub source: Entity,
+    pub target: Option<Entity>,
+    pub kind: TimedEdgeKind,
+    pub elapsed: Duration,
+    pub duration: Duration,
+}
+
+impl TimedEdge {
+    #[inline]
+    pub fn remaining(&self) -> Duration { self.duration.saturating_sub(self.elapsed) }
+
+    /// Progress in `[0, 1]`; 1 for zero-length timers.
+    #[inline]
+    pub fn fraction(&self) -> f32 {
+        if self.duration.is_zero() { return 1.0; }
+        (self.elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+}
+
+/// Read access to running After/Every timers, by edge, state or machine.
+/// Drives cast bars and cooldown displays straight from the chart.
+#[derive(SystemParam)]
+pub struct ChartTimers<'w, 's> {
+    machines: Query<'w, 's, &'static StateMachine>,
+    transitions: Query<'w, 's, &'static Transitions, With<Active>>,
+    edges: Query<'w, 's, (&'static Source, Option<&'static Target>, Has<AlwaysEdge>)>,
+    edge_timers: Query<'w, 's, &'static EdgeTimer>,
+    every_timers: Query<'w, 's, &'static EveryTimer>,
+}
+
+impl ChartTimers<'_, '_> {
+    /// The running timer of `edge`, if any.
+    pub fn edge(&self, edge: Entity) -> Option<TimedEdge> {
+        let (Source(source), target, always) = self.edges.get(edge).ok()?;
+        let (timer, kind) = if let Ok(EdgeTimer(timer)) = self.edge_timers.get(edge) {
+            if timer.is_finished() { return None; }
+            (timer, if always { TimedEdgeKind::Delayed } else { TimedEdgeKind::PendingEvent })
+        } else {
+            (&self.every_timers.get(edge).ok()?.timer, TimedEdgeKind::Every)
+        };
+        Some(TimedEdge {
+            edge,
+            source: *source,
+            target: target.map(|t| t.0),
+            kind,
+            elapsed: timer.elapsed(),
+            duration: timer.duration(),
+        })
+    }
+
+    /// Running timers on the outgoing edges of `state`, in priority order. Empty if `state` is inactive.
+    pub fn state(&self, state: Entity) -> Vec<TimedEdge> {
+        let Ok(transitions) = self.transitions.get(state) else { return Vec::new(); };
+        transitions.into_iter().filter_map(|&edge| self.edge(edge)).collect()
+    }
+
+    /// Running timers on the edges of every active state of the machine rooted at `root`.
+    pub fn machine(&self, root: Entity) -> Vec<TimedEdge> {
+        let Ok(machine) = self.machines.get(root) else { return Vec::new(); };
+        machine.active.iter().flat_map(|&state| self.state(state)).collect()
+    }
+}
+
 /// Pending event stored on an edge awaiting its After timer
 #[derive(Component)]
 pub struct PendingEvent<E: EntityEvent + Clone> {
@@ -152,11 +472,13 @@ where
     drop(installed);
     if already { return; }
 
+    let schedule = timer_schedule(app);
     app.add_observer(edge_event_listener::<E>)
         .add_observer(crate::transition_observer::<PhaseEvents<E::ExitEvent, E::EffectEvent, E::EntryEvent>>)
-        .add_systems(Update, tick_after_event_timers::<E>)
+        .add_systems(schedule, tick_after_event_timers::<E>.in_set(AfterTimerSystems))
         .add_observer(cancel_pending_event_on_exit::<E>)
-        .add_observer(replay_deferred_event::<E>);
+        .add_observer(replay_deferred_event::<E>)
+        .add_observer(crate::invoke::forward_to_invoked::<E>);
 }
 
 
@@ -258,20 +580,10 @@ fn try_fire_first_matching_edge_generic<E: TransitionEvent + RegisteredTransitio
     q_edge_target: &Query<&Target>,
     q_guards: &Query<&Guards>,
     q_child_of: &Query<&StateChildOf>,
-    q_defer: &mut Query<&mut DeferEvent<E>>,
-    q_active: &Query<(), With<Active>>,
     q_after: &Query<&After>,
     q_timer: &mut Query<&mut EdgeTimer>,
     commands: &mut Commands,
 ) -> bool {
-    // Check if this state should defer this event type
-    if let Ok(mut defer_event) = q_defer.get_mut(source) {
-        if q_active.get(source).is_ok() {
-            defer_event.defer_event(event.clone());
-            return false;
-        }
-    }
-
     let Ok(transitions) = q_transitions.get(source) else { return false; };
 
     for edge in transitions.into_iter().copied() {
@@ -285,8 +597,9 @@ fn try_fire_first_matching_edge_generic<E: TransitionEvent + RegisteredTransitio
             if let Ok(mut timer) = q_timer.get_mut(edge) {
                 timer.0.set_duration(after.duration);
                 timer.0.reset();
+                queue_delay_resolution(commands, edge);
             } else {
-                commands.entity(edge).insert(EdgeTimer(Timer::new(after.duration, TimerMode::Once)));
+                arm_after_timer(commands, edge, after);
             }
             commands.entity(edge).insert(PendingEvent::<E> { event: event.clone() });
             return true;
@@ -321,17 +634,32 @@ impl<E: EntityEvent + RegisteredTransitionEvent> Default for EventEdge<E> {
     }
 }
 
-/// A component that can be added to states to an event of a specific type.
-/// Event of type `E` that arrive while this state is active will be stored
-/// and replayed when the state is exited.
+/// A component that can be added to states to defer events of a specific type.
+/// Events of type `E` that arrive while this state (or any of its descendants) is active are
+/// queued and replayed in arrival order once the transition exiting this state completes.
 #[derive(Component)]
 pub struct DeferEvent<E: EntityEvent + RegisteredTransitionEvent> {
-    pub deferred: Option<E>,
+    pub deferred: VecDeque<E>,
+    /// Maximum number of queued events; ignored by `DeferOverflow::KeepAll`.
+    pub capacity: usize,
+    pub overflow: DeferOverflow,
+}
+
+/// What `DeferEvent` does when an event arrives while the queue is at capacity.
+#[derive(Reflect, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeferOverflow {
+    /// Ignore the capacity.
+    #[default]
+    KeepAll,
+    /// Discard the oldest queued event to make room.
+    DropOldest,
+    /// Discard the incoming event.
+    DropNewest,
 }
 
 impl<E: EntityEvent + RegisteredTransitionEvent> Default for DeferEvent<E> {
     fn default() -> Self {
-        Self { deferred: None }
+        Self { deferred: VecDeque::new(), capacity: usize::MAX, overflow: DeferOverflow::KeepAll }
     }
 }
 
@@ -339,13 +667,129 @@ impl<E: EntityEvent + RegisteredTransitionEvent> DeferEvent<E> {
     pub fn new() -> Self {
         Self::default()
     }
-    
-    pub fn defer_event(&mut self, event: E) {
-        self.deferred = Some(event);
+
+    /// A queue holding at most `capacity` events, applying `overflow` beyond that.
+    pub fn with_capacity(capacity: usize, overflow: DeferOverflow) -> Self {
+        Self { deferred: VecDeque::new(), capacity, overflow }
     }
-    
+
+    /// Queues `event`; returns false if it was discarded by the overflow policy.
+    pub fn defer_event(&mut self, event: E) -> bool {
+        if self.overflow != DeferOverflow::KeepAll && self.deferred.len() >= self.capacity {
+            match self.overflow {
+                DeferOverflow::DropNewest => return false,
+                DeferOverflow::DropOldest => {
+                    if self.deferred.pop_front().is_none() { return false; }
+                }
+                DeferOverflow::KeepAll => {}
+            }
+        }
+        self.deferred.push_back(event);
+        true
+    }
+
+    /// Removes and returns the oldest deferred event.
     pub fn take_deferred(&mut self) -> Option<E> {
-        std::mem::take(&mut self.deferred)
+        self.deferred.pop_front()
+    }
+}
+
+/// Events released by exited deferring states, replayed on the machine root by
+/// `replay_deferred_events` once the transition completes.
+#[derive(Component, Default)]
+pub struct DeferredReplay(Vec<QueuedEvent>);
+
+/// Command replaying the events in the machine's `DeferredReplay`, oldest first.
+pub(crate) fn replay_deferred_events(machine: Entity) -> impl Command {
+    move |world: &mut World| {
+        let Some(mut replay) = world.get_mut::<DeferredReplay>(machine) else { return; };
+        let events = std::mem::take(&mut replay.0);
+        for event in events {
+            event(world);
+        }
+    }
+}
+
+/// Freezes the machine whose root carries it: timers stop ticking, Always edges are not
+/// re-checked, and incoming transition events are queued or dropped per `PausedEvents`.
+/// Removing it resumes the machine, replaying queued events in arrival order.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+pub struct Paused {
+    pub events: PausedEvents,
+    #[reflect(ignore)]
+    queued: Vec<QueuedEvent>,
+}
+
+/// A transition event held back (by `Paused` or `DeferEvent`) for later replay.
+type QueuedEvent = Box<dyn FnOnce(&mut World) + Send + Sync>;
+
+/// Wraps `event` for replay. The transition it causes completes before the next one is replayed.
+fn queued_event<E: EntityEvent>(event: E) -> QueuedEvent
+where
+    for<'a> <E as Event>::Trigger<'a>: Default,
+{
+    Box::new(move |world: &mut World| {
+        world.trigger(event);
+        world.flush();
+    })
+}
+
+/// What happens to transition events sent to a `Paused` machine.
+#[derive(Reflect, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PausedEvents {
+    /// Keep them and process them in order on resume.
+    #[default]
+    Queue,
+    /// Discard them.
+    Drop,
+}
+
+impl Paused {
+    /// Pause, queueing incoming events.
+    pub fn queue() -> Self { Self::default() }
+
+    /// Pause, dropping incoming events.
+    pub fn drop_events() -> Self { Self { events: PausedEvents::Drop, ..default() } }
+
+    /// Number of events waiting for resume.
+    pub fn queued_len(&self) -> usize { self.queued.len() }
+
+    fn enqueue<E: EntityEvent + Clone>(&mut self, event: &E)
+    where
+        for<'a> <E as Event>::Trigger<'a>: Default,
+    {
+        if self.events == PausedEvents::Drop { return; }
+        let event = event.clone();
+        self.queued.push(queued_event(event));
+    }
+}
+
+/// When `Paused` is removed, replay its queued events and re-check Always edges of active
+/// states, whose guard changes were ignored while paused. After edges with a running
+/// `EdgeTimer` are left alone so they resume where they left off.
+pub fn resume_paused_machine(
+    remove: On<Remove, Paused>,
+    mut q_paused: Query<&mut Paused>,
+    q_sm: Query<&StateMachine>,
+    q_transitions: Query<&Transitions>,
+    mut q_guards: Query<&mut Guards, (With<AlwaysEdge>, Without<EdgeTimer>)>,
+    mut commands: Commands,
+) {
+    let root = remove.entity;
+    if let Ok(mut paused) = q_paused.get_mut(root) {
+        for replay in paused.queued.drain(..) {
+            commands.queue(replay);
+        }
+    }
+    let Ok(machine) = q_sm.get(root) else { return; };
+    for &state in machine.active.iter() {
+        let Ok(transitions) = q_transitions.get(state) else { continue; };
+        for &edge in transitions.into_iter() {
+            if let Ok(mut guards) = q_guards.get_mut(edge) {
+                guards.set_changed();
+            }
+        }
     }
 }
 
@@ -409,9 +853,37 @@ fn find_parallel_region_root(
     state
 }
 
+/// Walks up from `state` past every active state deferring `E` (a deferral covers the state's
+/// descendants). Returns the state the edge search should continue from (`None` if the deferral
+/// covers the whole path) and the nearest deferring state, which holds the event if nothing fires.
+fn skip_deferring_states<E: EntityEvent + RegisteredTransitionEvent>(
+    state: Entity,
+    q_child_of: &Query<&StateChildOf>,
+    q_defer: &Query<&mut DeferEvent<E>>,
+    q_active: &Query<(), With<Active>>,
+) -> (Option<Entity>, Option<Entity>) {
+    let path: Vec<Entity> = std::iter::once(state).chain(q_child_of.iter_ancestors(state)).collect();
+    let defers = |s: Entity| q_defer.contains(s) && q_active.contains(s);
+    let Some(outermost) = path.iter().rposition(|&s| defers(s)) else { return (Some(state), None); };
+    let nearest = path.iter().copied().find(|&s| defers(s));
+    (path.get(outermost + 1).copied(), nearest)
+}
+
+/// Queue `event` in `deferrer`; called at most once per event and machine.
+fn defer_in<E: EntityEvent + RegisteredTransitionEvent + Clone>(
+    deferrer: Entity,
+    event: &E,
+    q_defer: &mut Query<&mut DeferEvent<E>>,
+) {
+    if let Ok(mut defer_event) = q_defer.get_mut(deferrer) {
+        defer_event.defer_event(event.clone());
+    }
+}
+
 /// On event `E`, scan `Transitions` for a matching edge with `EventEdge<E>`, in priority order.
 fn edge_event_listener<E: TransitionEvent + RegisteredTransitionEvent + Clone>(
     transition_event: On<E>,
+    mut q_paused: Query<&mut Paused>,
     q_transitions: Query<&Transitions>,
     q_listener: Query<&EventEdge<E>>, 
     q_edge_target: Query<&Target>,
@@ -424,9 +896,22 @@ fn edge_event_listener<E: TransitionEvent + RegisteredTransitionEvent + Clone>(
     q_after: Query<&After>,
     mut q_timer: Query<&mut EdgeTimer>,
     mut commands: Commands,
-) {
+)
+where
+    for<'a> <E as Event>::Trigger<'a>: Default,
+{
     let event = transition_event.event();
     let machine_root = transition_event.event().event_target();
+
+    // Paused machines hold or drop events until resumed
+    if let Ok(mut paused) = q_paused.get_mut(q_child_of.root_ancestor(machine_root)) {
+        paused.enqueue(event);
+        return;
+    }
+
+    // The first deferring state met; it holds the event only if no edge outside the
+    // deferred subtrees consumes it, so the event is queued at most once per machine.
+    let mut deferrer: Option<Entity> = None;
     
     // If the event target is a machine root, try leaves/branches first (statechart-like), then fall back to root
     if let Ok(current) = q_sm.get(machine_root) {
@@ -438,32 +923,42 @@ fn edge_event_listener<E: TransitionEvent + RegisteredTransitionEvent + Clone>(
             let region_root = find_parallel_region_root(leaf, &q_child_of, &q_parallel);
             if fired_regions.contains(&region_root) { continue; }
 
+            // States deferring `E` hold it; only states above them may handle it
+            let (start, nearest) = skip_deferring_states(leaf, &q_child_of, &q_defer, &q_active);
+            deferrer = deferrer.or(nearest);
+            let Some(start) = start else { continue; };
+
             if try_fire_first_matching_edge_on_branch(
-                leaf, event, machine_root,
+                start, event, machine_root,
                 &q_transitions, &q_listener, &q_edge_target, &q_guards,
-                &q_child_of, &mut q_defer, &q_active, &q_after,
+                &q_child_of, &q_after,
                 &mut q_timer, &mut visited, &mut commands,
             ) {
                 fired_regions.insert(region_root);
             }
         }
 
-        // If no branch consumed the event, fall back to root-level transitions
-        if fired_regions.is_empty() {
-            let _ = try_fire_first_matching_edge(
-                machine_root, event, &q_transitions, &q_listener, &q_edge_target,
-                &q_guards, &q_child_of, &mut q_defer, &q_active,
-                &q_after, &mut q_timer, &mut commands,
-            );
+        if !fired_regions.is_empty() { return; }
+        if let Some(deferrer) = deferrer {
+            defer_in(deferrer, event, &mut q_defer);
+            return;
         }
+        // If no branch consumed the event, fall back to root-level transitions
+        let _ = try_fire_first_matching_edge(
+            machine_root, event, &q_transitions, &q_listener, &q_edge_target,
+            &q_guards, &q_child_of, &q_after, &mut q_timer, &mut commands,
+        );
         return;
     }
 
     // Otherwise, evaluate on the targeted state directly
+    if let (_, Some(deferrer)) = skip_deferring_states(machine_root, &q_child_of, &q_defer, &q_active) {
+        defer_in(deferrer, event, &mut q_defer);
+        return;
+    }
     try_fire_first_matching_edge(
         machine_root, event, &q_transitions, &q_listener, &q_edge_target,
-        &q_guards, &q_child_of, &mut q_defer, &q_active, 
-        &q_after, &mut q_timer, &mut commands,
+        &q_guards, &q_child_of, &q_after, &mut q_timer, &mut commands,
     );
 }
 
@@ -475,15 +970,13 @@ fn try_fire_first_matching_edge<E: TransitionEvent + RegisteredTransitionEvent +
     q_edge_target: &Query<&Target>,
     q_guards: &Query<&Guards>,
     q_child_of: &Query<&StateChildOf>,
-    q_defer: &mut Query<&mut DeferEvent<E>>,
-    q_active: &Query<(), With<Active>>,
     q_after: &Query<&After>,
     q_timer: &mut Query<&mut EdgeTimer>,
     commands: &mut Commands,
 ) -> bool {
     try_fire_first_matching_edge_generic(
         source, event, q_transitions, q_listener, q_edge_target,
-        q_guards, q_child_of, q_defer, q_active, q_after,
+        q_guards, q_child_of, q_after,
         q_timer, commands,
     )
 }
@@ -497,8 +990,6 @@ fn try_fire_first_matching_edge_on_branch<E: EntityEvent + Clone + TransitionEve
     q_edge_target: &Query<&Target>,
     q_guards: &Query<&Guards>,
     q_child_of: &Query<&StateChildOf>,
-    q_defer: &mut Query<&mut DeferEvent<E>>,
-    q_active: &Query<(), With<Active>>,
     q_after: &Query<&After>,
     q_timer: &mut Query<&mut EdgeTimer>,
     visited: &mut HashSet<Entity>,
@@ -521,8 +1012,6 @@ fn try_fire_first_matching_edge_on_branch<E: EntityEvent + Clone + TransitionEve
             q_edge_target,
             q_guards,
             q_child_of,
-            q_defer,
-            q_active,
             q_after,
             q_timer,
             commands,
@@ -543,6 +1032,7 @@ pub fn check_always_on_guards_changed(
     q_child_of: Query<&StateChildOf>,
     q_active: Query<(), With<Active>>,
     q_after: Query<&After>,
+    q_paused: Query<(), With<Paused>>,
     mut commands: Commands,
 ) {
     for (edge, guards, source, edge_target) in q_guards_changed.iter() {
@@ -551,6 +1041,10 @@ pub fn check_always_on_guards_changed(
 
         if !q_active.contains(source) { continue; }
 
+        // Paused machines re-check on resume
+        let root = q_child_of.root_ancestor(source);
+        if q_paused.contains(root) { continue; }
+
         // Only consider Always edges whose guard set changed to passing
         if !guards.check() { continue; }
 
@@ -560,10 +1054,8 @@ pub fn check_always_on_guards_changed(
 
         // Ensure edge has a valid target; then fire (or arm timer if delayed)
         if !edge_target { continue; }
-        let root = q_child_of.root_ancestor(source);
-        if q_after.get(edge).is_ok() {
-            let after = q_after.get(edge).unwrap();
-            commands.entity(edge).insert(EdgeTimer(Timer::new(after.duration, TimerMode::Once)));
+        if let Ok(after) = q_after.get(edge) {
+            arm_after_timer(&mut commands, edge, after);
         } else {
             commands.trigger(Transition { machine: root, source, edge, payload: () });
         }
@@ -581,13 +1073,41 @@ pub fn start_after_on_enter(
     let source = enter_state.target;
     let Ok(transitions) = q_transitions.get(source) else { return; };
     for edge in transitions.into_iter().copied() {
-        if q_after.get(edge).is_ok() && q_always.get(edge).is_ok() {
-            let after = q_after.get(edge).unwrap();
-            commands.entity(edge).insert(EdgeTimer(Timer::new(after.duration, TimerMode::Once)));
+        if !q_always.contains(edge) { continue; }
+        if let Ok(after) = q_after.get(edge) {
+            arm_after_timer(&mut commands, edge, after);
         }
     }
 }
 
+/// Start what entering its source would have started for an edge added to (or retargeted in)
+/// an active state: its `Every` or `After` timer if not already running, or an immediate check
+/// of an unguarded `AlwaysEdge`. The caller ensures the source is active.
+pub(crate) fn arm_edge(world: &mut World, root: Entity, edge: Entity) {
+    let Some(source) = world.get::<Source>(edge).map(|source| source.0) else { return; };
+    let every_timer = world.get::<Every>(edge).map(EveryTimer::new);
+    if let Some(timer) = every_timer {
+        if !world.entity(edge).contains::<EveryTimer>() {
+            world.entity_mut(edge).insert(timer);
+        }
+    }
+    if !world.entity(edge).contains::<AlwaysEdge>() { return; }
+
+    if let Some(duration) = world.get::<After>(edge).map(|after| after.duration) {
+        if !world.entity(edge).contains::<EdgeTimer>() {
+            arm_after_timer(&mut world.commands(), edge, &After::new(duration));
+            world.flush();
+        }
+        return;
+    }
+    let allowed = world.get::<Guards>(edge).is_none_or(|guards| guards.check())
+        && world.entity(edge).contains::<Target>();
+    if allowed {
+        world.trigger(Transition { machine: root, source, edge, payload: () });
+        world.flush();
+    }
+}
+
 /// On ExitState(source), cancel timers for any After edges.
 pub fn cancel_after_on_exit(
     exit_state: On<crate::ExitState>,
@@ -604,6 +1124,38 @@ pub fn cancel_after_on_exit(
     }
 }
 
+/// On EnterState(source), start repeating timers for any Every edges.
+pub fn start_every_on_enter(
+    enter_state: On<EnterState>,
+    q_transitions: Query<&Transitions>,
+    q_every: Query<&Every>,
+    mut commands: Commands,
+) {
+    let source = enter_state.target;
+    let Ok(transitions) = q_transitions.get(source) else { return; };
+    for edge in transitions.into_iter().copied() {
+        if let Ok(every) = q_every.get(edge) {
+            commands.entity(edge).insert(EveryTimer::new(every));
+        }
+    }
+}
+
+/// On ExitState(source), cancel repeating timers for any Every edges.
+pub fn cancel_every_on_exit(
+    exit_state: On<crate::ExitState>,
+    q_transitions: Query<&Transitions>,
+    q_every: Query<(), With<Every>>,
+    mut commands: Commands,
+) {
+    let source = exit_state.target;
+    let Ok(transitions) = q_transitions.get(source) else { return; };
+    for edge in transitions.into_iter().copied() {
+        if q_every.contains(edge) {
+            commands.entity(edge).remove::<EveryTimer>();
+        }
+    }
+}
+
 /// During TransitionActions, if an edge has ResetEdge, emit ResetSubtree for its scope
 pub(crate) fn reset_on_transition_actions(
     transition_action: On<crate::TransitionActions>,
@@ -643,7 +1195,7 @@ pub(crate) fn reset_on_transition_actions(
 
 /// Tick After timers and fire the first due transition per active source, respecting Transitions order.
 pub fn tick_after_system(
-    time: Res<Time>,
+    timer_delta: TimerDelta,
     q_transitions: Query<(Entity, &Transitions), With<Active>>, // active source states only
     mut q_timer: Query<&mut EdgeTimer>,
     q_after: Query<&After>,
@@ -654,12 +1206,14 @@ pub fn tick_after_system(
     mut commands: Commands,
 ) {
     for (source, transitions) in q_transitions.iter() {
+        let root = q_child_of.root_ancestor(source);
+        let Some(delta) = timer_delta.for_machine(root) else { continue; };
         // Walk edges in priority order; fire first eligible
         for edge in transitions.into_iter().copied() {
             if q_after.get(edge).is_err() { continue; }
             if q_always.get(edge).is_err() { continue; }
             let Ok(mut timer) = q_timer.get_mut(edge) else { continue; };
-            timer.0.tick(time.delta());
+            timer.0.tick(delta);
             if !timer.0.just_finished() { continue; }
 
             // Validate edge (guards and target) before firing
@@ -673,14 +1227,42 @@ pub fn tick_after_system(
             commands.entity(edge).remove::<EdgeTimer>();
 
             // Emit transition to the machine root with empty payload
-            let root = q_child_of.root_ancestor(source);
             commands.trigger(Transition { machine: root, source, edge, payload: () });
             break; // only one delayed transition per source per frame
         }
     }
 }
 
-/// Generic system to replay deferred event when a state exits.
+/// Tick Every timers and fire their edges once per elapsed interval while the source is active.
+/// Blocked edges skip the repetition without counting it.
+pub fn tick_every_system(
+    timer_delta: TimerDelta,
+    q_transitions: Query<(Entity, &Transitions), With<Active>>,
+    mut q_timer: Query<(&Every, &mut EveryTimer)>,
+    q_guards: Query<&Guards>,
+    q_edge_target: Query<&Target>,
+    q_child_of: Query<&StateChildOf>,
+    mut commands: Commands,
+) {
+    for (source, transitions) in q_transitions.iter() {
+        let root = q_child_of.root_ancestor(source);
+        let Some(delta) = timer_delta.for_machine(root) else { continue; };
+        for edge in transitions.into_iter().copied() {
+            let Ok((every, mut every_timer)) = q_timer.get_mut(edge) else { continue; };
+            every_timer.timer.tick(delta);
+            for _ in 0..every_timer.timer.times_finished_this_tick() {
+                if every.max_repetitions.is_some_and(|max| every_timer.repetitions >= max) { break; }
+                if !validate_edge_basic(edge, &q_guards, &q_edge_target) { continue; }
+                every_timer.repetitions += 1;
+                commands.trigger(Transition { machine: root, source, edge, payload: () });
+            }
+        }
+    }
+}
+
+/// Generic observer releasing deferred events when a deferring state exits. They are
+/// replayed in order after the exiting transition completes, so the new configuration
+/// may handle them, or defer them again.
 pub fn replay_deferred_event<E: EntityEvent + RegisteredTransitionEvent + Clone>(
     exit_state: On<ExitState>,
     mut q_defer: Query<&mut DeferEvent<E>>,
@@ -689,18 +1271,24 @@ pub fn replay_deferred_event<E: EntityEvent + RegisteredTransitionEvent + Clone>
 where
     for<'a> <E as Event>::Trigger<'a>: Default,
 {
-    let exited_state = exit_state.target;
-
-    if let Ok(mut defer_event) = q_defer.get_mut(exited_state) {
-        if let Some(deferred) = defer_event.take_deferred() {
-            commands.trigger(deferred);
-        }
-    }
+    let Ok(mut defer_event) = q_defer.get_mut(exit_state.target) else { return; };
+    if defer_event.deferred.is_empty() { return; }
+
+    let released: Vec<QueuedEvent> = defer_event
+        .deferred
+        .drain(..)
+        .map(queued_event)
+        .collect();
+    commands
+        .entity(exit_state.state_machine)
+        .entry::<DeferredReplay>()
+        .or_default()
+        .and_modify(move |mut replay| replay.0.extend(released));
 }
 
 /// Timer system for event edges with After; fire when due
 pub fn tick_after_event_timers<E: TransitionEvent + RegisteredTransitionEvent + Clone + 'static>(
-    time: Res<Time>,
+    timer_delta: TimerDelta,
     mut q_timer: Query<(Entity, &mut EdgeTimer, &PendingEvent<E>), With<EventEdge<E>>>,
     q_after: Query<&After>,
     q_guards: Query<&Guards>,
@@ -721,7 +1309,9 @@ pub fn tick_after_event_timers<E: TransitionEvent + RegisteredTransitionEvent +
             continue;
         }
 
-        timer.0.tick(time.delta());
+        let root = q_child_of.root_ancestor(*source);
+        let Some(delta) = timer_delta.for_machine(root) else { continue; };
+        timer.0.tick(delta);
         if !timer.0.just_finished() { continue; }
 
         // Validate edge (guards and target) before firing
@@ -739,7 +1329,6 @@ pub fn tick_after_event_timers<E: TransitionEvent + RegisteredTransitionEvent +
 
         // Cleanup timer/pending and fire the transition to machine root
         cleanup_edge_timer_and_pending::<E>(&mut commands, edge);
-        let root = q_child_of.root_ancestor(*source);
         commands.trigger(Transition { machine: root, source: *source, edge, payload });
     }
 }
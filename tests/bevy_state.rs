@@ -1,4 +1,6 @@
-use bevy::prelude::*;
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
 use bevy_gearbox::{prelude::*, GearboxPlugin};
 
 fn test_app() -> App {
@@ -120,3 +122,144 @@ fn commands_helper_emits_to_marked_chart_root() {
 }
 
 
+
+#[derive(Component)]
+struct Enemy;
+
+#[derive(Component)]
+struct Alertable;
+
+#[derive(Component)]
+#[relationship(relationship_target = Squad)]
+struct InSquad(Entity);
+
+#[derive(Component)]
+#[relationship_target(relationship = InSquad)]
+struct Squad(Vec<Entity>);
+
+#[derive(Resource, Default)]
+struct Reports(Vec<SendReport>);
+
+fn record_report(report: On<ChartSendReport>, mut reports: ResMut<Reports>) {
+    reports.0.push(report.report);
+}
+
+/// root -> { S, T }, with S --Go--> T when `listening`. S carries `Alertable`.
+fn spawn_enemy(app: &mut App, listening: bool) -> (Entity, Entity) {
+    let root = app.world_mut().spawn(Enemy).id();
+    let s = app.world_mut().spawn((StateChildOf(root), Alertable)).id();
+    let t = app.world_mut().spawn(StateChildOf(root)).id();
+    if listening {
+        app.world_mut().spawn((Source(s), Target(t), EventEdge::<Go>::default()));
+    }
+    app.world_mut().entity_mut(root).insert((InitialState(s), StateMachine::new()));
+    (root, t)
+}
+
+#[test]
+fn send_to_charts_routes_and_reports() {
+    let mut app = test_app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(20)));
+    app.init_resource::<Reports>();
+    app.add_observer(record_report);
+
+    let (a, a_t) = spawn_enemy(&mut app, true);
+    let (b, _) = spawn_enemy(&mut app, false);
+    let (c, c_t) = spawn_enemy(&mut app, true);
+    let squad = app.world_mut().spawn_empty().id();
+    app.world_mut().entity_mut(a).insert(InSquad(squad));
+    app.world_mut().entity_mut(b).insert(InSquad(squad));
+    app.update();
+
+    // Squad members only: a consumes, b ignores
+    app.world_mut().commands().send_to_charts(ChartRoute::related::<Squad>(squad), Go);
+    app.update();
+    assert_eq!(app.world().resource::<Reports>().0, vec![SendReport { received: 2, consumed: 1 }]);
+    assert!(app.world().get::<StateMachine>(a).unwrap().active_leaves.contains(&a_t));
+
+    // Machines still in an Alertable state: b and c
+    app.world_mut().commands().send_to_charts(ChartRoute::in_state_with::<Alertable>(), Go);
+    app.update();
+    assert_eq!(app.world().resource::<Reports>().0[1], SendReport { received: 2, consumed: 1 });
+    assert!(app.world().get::<StateMachine>(c).unwrap().active_leaves.contains(&c_t));
+
+    // Every marked machine, delayed
+    app.world_mut().commands().send_to_charts_after(Duration::from_millis(30), ChartRoute::marked::<Enemy>(), Go);
+    app.update();
+    assert_eq!(app.world().resource::<Reports>().0.len(), 2, "not sent before the delay");
+    app.update();
+    assert_eq!(app.world().resource::<Reports>().0[2], SendReport { received: 3, consumed: 0 });
+
+    // Delays on a machine's clock hold while it is paused
+    app.world_mut().commands().send_to_charts_after_on(a, Duration::from_millis(30), ChartRoute::roots([a]), Go);
+    app.world_mut().entity_mut(a).insert(Paused::default());
+    app.update();
+    app.update();
+    assert_eq!(app.world().resource::<Reports>().0.len(), 3, "paused clock holds the send");
+    app.world_mut().entity_mut(a).remove::<Paused>();
+    app.update();
+    app.update();
+    assert_eq!(app.world().resource::<Reports>().0.len(), 4, "sent once the clock resumes");
+}
+
+#[derive(States, Component, Clone, Copy, Eq, PartialEq, Hash, Debug, Default)]
+enum Screen {
+    #[default]
+    Title,
+    Game,
+    Credits,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+struct InGame;
+
+impl ComputedStates for InGame {
+    type SourceStates = Screen;
+    fn compute(screen: Screen) -> Option<Self> {
+        (screen == Screen::Game).then_some(InGame)
+    }
+}
+
+#[test]
+fn two_way_bridge_follows_state_changes_and_falls_back_on_exit() {
+    let mut app = test_app();
+    app.init_state::<Screen>();
+    app.add_computed_state::<InGame>();
+    app.add_state_bridge_with(StateBridge::new().two_way().with_fallback(Screen::Title));
+
+    // root -> { Title, Game, Credits, Loading (unbridged) }
+    let root = app.world_mut().spawn_empty().id();
+    let title = app.world_mut().spawn((StateChildOf(root), Screen::Title)).id();
+    let game = app.world_mut().spawn((StateChildOf(root), Screen::Game)).id();
+    let loading = app.world_mut().spawn(StateChildOf(root)).id();
+    app.world_mut().spawn((StateChildOf(root), Screen::Credits));
+    app.world_mut().spawn((Source(game), Target(loading), EventEdge::<Go>::default()));
+    app.world_mut().entity_mut(root).insert((InitialState(game), StateMachine::new()));
+    app.update();
+    app.update();
+    assert_eq!(**app.world().resource::<State<Screen>>(), Screen::Game);
+    assert!(app.world().get_resource::<State<InGame>>().is_some(), "computed state derived from the chart");
+
+    // Other code changes the Bevy state: the chart follows, without echoing back
+    app.world_mut().resource_mut::<NextState<Screen>>().set(Screen::Title);
+    app.update();
+    app.update();
+    let leaves = |app: &App| app.world().get::<StateMachine>(root).unwrap().active_leaves.clone();
+    assert!(leaves(&app).contains(&title));
+    assert_eq!(**app.world().resource::<State<Screen>>(), Screen::Title);
+    assert!(matches!(*app.world().resource::<NextState<Screen>>(), NextState::Unchanged));
+    assert!(app.world().get::<Transitions>(root).is_none(), "bridge edges stay out of the chart");
+
+    // Leaving all bridged nodes sets the fallback; the chart isn't pulled back by it
+    app.world_mut().resource_mut::<NextState<Screen>>().set(Screen::Game);
+    app.update();
+    app.update();
+    assert!(leaves(&app).contains(&game));
+    app.world_mut().commands().trigger(Go(root));
+    app.update();
+    app.update();
+    app.update();
+    assert!(leaves(&app).contains(&loading));
+    assert_eq!(**app.world().resource::<State<Screen>>(), Screen::Title);
+    assert!(app.world().get_resource::<State<InGame>>().is_none());
+}
@@ -1,4 +1,6 @@
-use bevy::prelude::*;
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
 use bevy_gearbox::{prelude::*, GearboxPlugin};
 
 fn test_app() -> App {
@@ -101,3 +103,453 @@ fn apply_param_guards_manage_guard_presence() {
 }
 
 
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug, Default)]
+enum Stance { #[default] Idle, Walk, Run }
+
+#[derive(Component)]
+struct Mover { stance: Stance, ammo: u32 }
+
+struct PStance; struct PAmmo;
+
+impl ParamBinding<Mover, Stance> for PStance { fn extract(m: &Mover) -> Stance { m.stance } }
+impl ParamBinding<Mover, u32> for PAmmo { fn extract(m: &Mover) -> u32 { m.ammo } }
+
+#[test]
+fn generic_params_sync_and_drive_conditions() {
+    let mut app = test_app();
+
+    let root = app.world_mut().spawn((
+        Mover { stance: Stance::Idle, ammo: 0 },
+        Param::<PStance, Stance>::default(),
+        Param::<PAmmo, u32>::default(),
+    )).id();
+    let s = app.world_mut().spawn(StateChildOf(root)).id();
+    let t = app.world_mut().spawn(StateChildOf(root)).id();
+
+    let edge = app.world_mut().spawn((
+        Source(s),
+        Target(t),
+        OneOf::<PStance, Stance>::new([Stance::Walk, Stance::Run]),
+        GreaterThan::<PAmmo, u32>::new(2),
+        NotEquals::<PAmmo, u32>::new(5),
+    )).id();
+
+    app.add_systems(Update, (
+        (sync_param::<Mover, PStance, Stance>, sync_param::<Mover, PAmmo, u32>),
+        (
+            apply_param_guards::<OneOf<PStance, Stance>>,
+            apply_param_guards::<GreaterThan<PAmmo, u32>>,
+            apply_param_guards::<NotEquals<PAmmo, u32>>,
+        ),
+    ).chain());
+
+    app.update();
+    assert!(!app.world().get::<Guards>(edge).unwrap().check(), "idle with no ammo should block");
+
+    app.world_mut().get_mut::<Mover>(root).unwrap().stance = Stance::Run;
+    app.world_mut().get_mut::<Mover>(root).unwrap().ammo = 5;
+    app.update();
+    assert_eq!(app.world().get::<Param<PStance, Stance>>(root).unwrap().get(), Stance::Run);
+    assert!(!app.world().get::<Guards>(edge).unwrap().check(), "excluded ammo value should still block");
+
+    app.world_mut().get_mut::<Mover>(root).unwrap().ammo = 3;
+    app.update();
+    assert!(app.world().get::<Guards>(edge).unwrap().check(), "all conditions pass -> guards cleared");
+}
+
+struct Jump;
+
+#[test]
+fn trigger_param_fires_once_and_is_consumed() {
+    let mut app = test_app();
+    app.add_trigger_param::<Jump>();
+
+    let root = app.world_mut().spawn(TriggerParam::<Jump>::default()).id();
+    let s = app.world_mut().spawn(StateChildOf(root)).id();
+    let t = app.world_mut().spawn(StateChildOf(root)).id();
+
+    // S --(Always, TriggerSet<Jump>)--> T and T --(Always, TriggerSet<Jump>)--> S
+    app.world_mut().spawn((Source(s), Target(t), AlwaysEdge, TriggerSet::<Jump>::default()));
+    app.world_mut().spawn((Source(t), Target(s), AlwaysEdge, TriggerSet::<Jump>::default()));
+
+    app.world_mut().entity_mut(root).insert((InitialState(s), StateMachine::new()));
+    app.update();
+    assert!(app.world().get::<StateMachine>(root).unwrap().active_leaves.contains(&s));
+
+    // Setting the trigger enables exactly one hop
+    app.world_mut().get_mut::<TriggerParam<Jump>>(root).unwrap().set();
+    app.update();
+    assert!(app.world().get::<StateMachine>(root).unwrap().active_leaves.contains(&t), "trigger should fire S -> T");
+    assert!(!app.world().get::<TriggerParam<Jump>>(root).unwrap().is_set(), "trigger should be consumed");
+
+    app.update();
+    assert!(app.world().get::<StateMachine>(root).unwrap().active_leaves.contains(&t), "consumed trigger must not fire T -> S");
+}
+
+#[test]
+fn trigger_param_unused_is_cleared_at_end_of_frame() {
+    let mut app = test_app();
+    app.add_trigger_param::<Jump>();
+
+    let root = app.world_mut().spawn(TriggerParam::<Jump>::default()).id();
+    let s = app.world_mut().spawn(StateChildOf(root)).id();
+    let t = app.world_mut().spawn(StateChildOf(root)).id();
+    let u = app.world_mut().spawn(StateChildOf(root)).id();
+
+    // Only T listens for the trigger; the machine sits in S
+    app.world_mut().spawn((Source(t), Target(u), AlwaysEdge, TriggerSet::<Jump>::default()));
+    app.world_mut().entity_mut(root).insert((InitialState(s), StateMachine::new()));
+    app.update();
+
+    app.world_mut().get_mut::<TriggerParam<Jump>>(root).unwrap().set();
+    app.update();
+    assert!(!app.world().get::<TriggerParam<Jump>>(root).unwrap().is_set(), "unused trigger should be cleared");
+}
+
+fn set_jump_late(mut q_params: Query<&mut TriggerParam<Jump>>, mut armed: Local<bool>) {
+    if *armed { return; }
+    for mut param in &mut q_params {
+        param.set();
+        *armed = true;
+    }
+}
+
+#[test]
+fn trigger_param_set_after_guards_survives_to_next_frame() {
+    let mut app = test_app();
+    app.add_trigger_param::<Jump>();
+
+    let root = app.world_mut().spawn(TriggerParam::<Jump>::default()).id();
+    let s = app.world_mut().spawn(StateChildOf(root)).id();
+    let t = app.world_mut().spawn(StateChildOf(root)).id();
+    app.world_mut().spawn((Source(s), Target(t), AlwaysEdge, TriggerSet::<Jump>::default()));
+    app.world_mut().entity_mut(root).insert((InitialState(s), StateMachine::new()));
+    app.update();
+
+    // Set in PostUpdate, after the guards ran and before the end-of-frame reset
+    app.add_systems(PostUpdate, set_jump_late);
+    app.update();
+    assert!(app.world().get::<TriggerParam<Jump>>(root).unwrap().is_set(), "late trigger is kept");
+    assert!(app.world().get::<StateMachine>(root).unwrap().active_leaves.contains(&s));
+
+    app.update();
+    assert!(app.world().get::<StateMachine>(root).unwrap().active_leaves.contains(&t), "late trigger fires next frame");
+    assert!(!app.world().get::<TriggerParam<Jump>>(root).unwrap().is_set(), "and is consumed");
+}
+
+#[derive(Component)]
+#[relationship(relationship_target = TargetedBy)]
+struct Targeting(Entity);
+
+#[derive(Component)]
+#[relationship_target(relationship = Targeting)]
+struct TargetedBy(Vec<Entity>);
+
+#[test]
+fn param_scope_reads_from_ancestor_override_or_related_entity() {
+    let mut app = test_app();
+    app.add_systems(Update, (
+        sync_param_scope_from_relationship::<Targeting>,
+        apply_bool_param_guards::<PB>,
+    ).chain());
+
+    // root (PB=false) -> sub (PB=true override) -> s
+    let root = app.world_mut().spawn(BoolParam::<PB>::new(false)).id();
+    let sub = app.world_mut().spawn((StateChildOf(root), BoolParam::<PB>::new(true))).id();
+    let s = app.world_mut().spawn(StateChildOf(sub)).id();
+    let t = app.world_mut().spawn(StateChildOf(sub)).id();
+
+    let root_scoped = app.world_mut().spawn((Source(s), Target(t), BoolEquals::<PB>::new(true))).id();
+    let nearest = app.world_mut().spawn((Source(s), Target(t), BoolEquals::<PB>::new(true), ParamScope::NearestAncestor)).id();
+    let related = app.world_mut().spawn((Source(s), Target(t), BoolEquals::<PB>::new(true), ParamFromRelationship::<Targeting>::default())).id();
+
+    app.update();
+    let blocked = |app: &App, e: Entity| !app.world().get::<Guards>(e).map(|g| g.check()).unwrap_or(true);
+    assert!(blocked(&app, root_scoped), "root value is false");
+    assert!(!blocked(&app, nearest), "sub-chart override is true");
+    assert!(blocked(&app, related), "no related entity yet");
+
+    // Root starts targeting an enemy whose PB is true
+    let enemy = app.world_mut().spawn(BoolParam::<PB>::new(true)).id();
+    app.world_mut().entity_mut(root).insert(Targeting(enemy));
+    app.update();
+    assert!(!blocked(&app, related), "related enemy value is true");
+    assert_eq!(app.world().get::<ParamScope>(related), Some(&ParamScope::Entity(enemy)));
+}
+
+#[test]
+fn param_guards_only_reevaluate_on_change() {
+    let mut app = test_app();
+    app.add_systems(Update, apply_float_param_guards::<PF>);
+
+    let root = app.world_mut().spawn(FloatParam::<PF>::new(0.0)).id();
+    let s = app.world_mut().spawn(StateChildOf(root)).id();
+    let t = app.world_mut().spawn(StateChildOf(root)).id();
+    let edge = app.world_mut().spawn((Source(s), Target(t), FloatInRange::<PF>::new(1.0, 2.0, 0.0))).id();
+
+    app.update();
+    assert!(!app.world().get::<Guards>(edge).unwrap().check());
+    assert!(app.world().get::<ParamDependents<PF, f32>>(root).unwrap().edges().any(|e| e == edge), "edge indexed on root");
+
+    // Clearing guards behind the system's back is not undone while nothing changed
+    app.world_mut().get_mut::<Guards>(edge).unwrap().guards.clear();
+    app.update();
+    assert!(app.world().get::<Guards>(edge).unwrap().check(), "unchanged param must not re-evaluate");
+
+    // Touching the param re-evaluates dependents
+    app.world_mut().get_mut::<FloatParam<PF>>(root).unwrap().set(5.0);
+    app.update();
+    assert!(!app.world().get::<Guards>(edge).unwrap().check(), "changed param re-evaluates its edges");
+
+    // Edges added later are picked up
+    let late = app.world_mut().spawn((Source(s), Target(t), FloatInRange::<PF>::new(4.0, 6.0, 0.0))).id();
+    app.update();
+    assert!(app.world().get::<Guards>(late).map(|g| g.check()).unwrap_or(true), "late edge passes");
+}
+
+#[derive(Component)]
+struct Stamina(f32);
+struct CanSprint;
+
+impl ParamBinding<Stamina, bool> for CanSprint { fn extract(s: &Stamina) -> bool { s.0 > 10.0 } }
+
+register_param_binding!(Stamina, CanSprint, bool);
+register_param_condition!(BoolEquals<CanSprint>);
+
+#[test]
+fn inventory_registered_params_sync_and_guard_without_manual_systems() {
+    let mut app = test_app();
+    // Registering again by hand is deduped
+    app.add_param_condition::<BoolEquals<CanSprint>>();
+
+    let root = app.world_mut().spawn((Stamina(0.0), BoolParam::<CanSprint>::default())).id();
+    let walk = app.world_mut().spawn(StateChildOf(root)).id();
+    let sprint = app.world_mut().spawn(StateChildOf(root)).id();
+    app.world_mut().spawn((Source(walk), Target(sprint), AlwaysEdge, BoolEquals::<CanSprint>::new(true)));
+    // Let the guard be applied before the machine starts
+    app.update();
+    app.world_mut().entity_mut(root).insert((InitialState(walk), StateMachine::new()));
+    app.update();
+    assert!(app.world().get::<StateMachine>(root).unwrap().active_leaves.contains(&walk));
+
+    app.world_mut().get_mut::<Stamina>(root).unwrap().0 = 50.0;
+    app.update();
+    assert!(app.world().get::<StateMachine>(root).unwrap().active_leaves.contains(&sprint), "auto-registered param should drive the edge");
+}
+
+#[test]
+fn float_in_range_hysteresis_remembers_previous_result() {
+    let mut app = test_app();
+    app.add_systems(Update, apply_float_param_guards::<PF>);
+
+    let root = app.world_mut().spawn(FloatParam::<PF>::new(5.0)).id();
+    let s = app.world_mut().spawn(StateChildOf(root)).id();
+    let t = app.world_mut().spawn(StateChildOf(root)).id();
+    let edge = app.world_mut().spawn((Source(s), Target(t), FloatInRange::<PF>::new(0.0, 10.0, 1.0))).id();
+
+    let step = |app: &mut App, value: f32| -> bool {
+        app.world_mut().get_mut::<FloatParam<PF>>(root).unwrap().set(value);
+        app.update();
+        app.world().get::<Guards>(edge).map(|g| g.check()).unwrap_or(true)
+    };
+
+    assert!(step(&mut app, 5.0), "inside range passes");
+    assert!(step(&mut app, 10.5), "stays satisfied within the exit margin");
+    assert!(!step(&mut app, 11.5), "leaving by more than the margin blocks");
+    assert!(!step(&mut app, 10.0), "must re-enter past the margin");
+    assert!(!step(&mut app, 9.5), "still within the entry margin");
+    assert!(step(&mut app, 8.5), "well inside the range passes again");
+}
+
+#[test]
+fn range_hysteresis_wider_than_the_range_is_clamped() {
+    assert_eq!(FloatInRange::<PF>::new(0.0, 4.0, 10.0).hysteresis, 2.0);
+    assert_eq!(FloatInRange::<PF>::new(0.0, 4.0, -1.0).hysteresis, 0.0);
+    assert_eq!(IntInRange::<PI>::new(0, 5, 10).hysteresis, 2);
+    assert_eq!(IntInRange::<PI>::new(i32::MIN, i32::MAX, i32::MAX).hysteresis, i32::MAX);
+
+    let mut app = test_app();
+    app.add_systems(Update, apply_int_param_guards::<PI>);
+    let root = app.world_mut().spawn(IntParam::<PI>::new(2)).id();
+    let s = app.world_mut().spawn(StateChildOf(root)).id();
+    let t = app.world_mut().spawn(StateChildOf(root)).id();
+    let edge = app.world_mut().spawn((Source(s), Target(t), IntInRange::<PI>::new(0, 4, 10))).id();
+    let step = |app: &mut App, value: i32| -> bool {
+        app.world_mut().get_mut::<IntParam<PI>>(root).unwrap().set(value);
+        app.update();
+        app.world().get::<Guards>(edge).map(|g| g.check()).unwrap_or(true)
+    };
+
+    assert!(step(&mut app, 2));
+    assert!(!step(&mut app, 7), "leaving by more than the clamped margin blocks");
+    assert!(step(&mut app, 2), "the range can be re-entered");
+}
+
+#[test]
+fn int_in_range_min_hold_delays_guard_flip() {
+    let mut app = test_app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(20)));
+    app.add_systems(Update, apply_int_param_guards::<PI>);
+
+    let root = app.world_mut().spawn(IntParam::<PI>::new(5)).id();
+    let s = app.world_mut().spawn(StateChildOf(root)).id();
+    let t = app.world_mut().spawn(StateChildOf(root)).id();
+    let edge = app.world_mut().spawn((
+        Source(s),
+        Target(t),
+        IntInRange::<PI>::new(0, 10, 0).with_min_hold(Duration::from_millis(50)),
+    )).id();
+    let passes = |app: &App| app.world().get::<Guards>(edge).map(|g| g.check()).unwrap_or(true);
+
+    app.update();
+    assert!(passes(&app));
+
+    // A brief excursion out of range does not flip the guard
+    app.world_mut().get_mut::<IntParam<PI>>(root).unwrap().set(20);
+    app.update();
+    assert!(passes(&app), "flip is held back");
+    app.world_mut().get_mut::<IntParam<PI>>(root).unwrap().set(5);
+    app.update();
+    assert!(passes(&app), "returning before the hold cancels the flip");
+
+    // A sustained excursion flips once the hold elapses, without further param changes
+    app.world_mut().get_mut::<IntParam<PI>>(root).unwrap().set(20);
+    app.update();
+    assert!(passes(&app));
+    app.update();
+    app.update();
+    assert!(passes(&app), "still within the hold");
+    app.update();
+    assert!(!passes(&app), "flip applied after min hold");
+}
+
+struct Health; struct MaxHealth; struct Ammo;
+
+#[derive(Resource)]
+struct ReloadThreshold(i32);
+
+#[test]
+fn compare_conditions_read_params_resources_and_expressions() {
+    let mut app = test_app();
+    app.add_compare_condition::<Ratio<ParamValue<Health>, ParamValue<MaxHealth>>, Const>()
+        .add_compare_condition::<ParamValue<Ammo, i32>, ResourceValue<ReloadThreshold>>()
+        .add_compare_condition::<Abs<Difference<ParamValue<Health>, ParamValue<MaxHealth>>>, Const>();
+    app.insert_resource(ReloadThreshold(3));
+
+    let root = app.world_mut().spawn((
+        FloatParam::<Health>::new(100.0),
+        FloatParam::<MaxHealth>::new(100.0),
+        IntParam::<Ammo>::new(10),
+    )).id();
+    let s = app.world_mut().spawn(StateChildOf(root)).id();
+    let t = app.world_mut().spawn(StateChildOf(root)).id();
+    let flee = app.world_mut().spawn((
+        Source(s),
+        Target(t),
+        Compare::new(Ratio(ParamValue::<Health>::new(), ParamValue::<MaxHealth>::new()), CompareOp::Less, Const(0.25)),
+    )).id();
+    let reload = app.world_mut().spawn((
+        Source(s),
+        Target(t),
+        Compare::new(ParamValue::<Ammo, i32>::new(), CompareOp::LessOrEqual, ResourceValue::new(|r: &ReloadThreshold| r.0 as f64)),
+    )).id();
+    let wounded = app.world_mut().spawn((
+        Source(s),
+        Target(t),
+        Compare::new(Abs(Difference(ParamValue::<Health>::new(), ParamValue::<MaxHealth>::new())), CompareOp::GreaterOrEqual, Const(50.0)),
+    )).id();
+    let passes = |app: &App, edge: Entity| app.world().get::<Guards>(edge).map(|g| g.check()).unwrap_or(true);
+
+    app.update();
+    assert!(!passes(&app, flee));
+    assert!(!passes(&app, reload));
+    assert!(!passes(&app, wounded));
+
+    app.world_mut().get_mut::<FloatParam<Health>>(root).unwrap().set(20.0);
+    app.update();
+    assert!(passes(&app, flee), "health ratio below threshold");
+    assert!(passes(&app, wounded), "absolute difference reached");
+
+    // Param against resource: changing either side re-evaluates
+    app.world_mut().resource_mut::<ReloadThreshold>().0 = 10;
+    app.update();
+    assert!(passes(&app, reload));
+
+    // Division by zero is unavailable and blocks
+    app.world_mut().get_mut::<FloatParam<MaxHealth>>(root).unwrap().set(0.0);
+    app.update();
+    assert!(!passes(&app, flee));
+
+    // Change-driven: both operands index the edge, and nothing re-runs while they are unchanged
+    let indexed = |app: &App, edge: Entity| {
+        app.world().get::<ParamDependents<Health, f32>>(root).unwrap().edges().any(|e| e == edge)
+            && app.world().get::<ParamDependents<MaxHealth, f32>>(root).unwrap().edges().any(|e| e == edge)
+    };
+    assert!(indexed(&app, flee) && indexed(&app, wounded));
+    app.world_mut().get_mut::<Guards>(flee).unwrap().guards.clear();
+    app.update();
+    assert!(passes(&app, flee), "unchanged operands must not re-evaluate");
+    app.world_mut().get_mut::<FloatParam<MaxHealth>>(root).unwrap().set(0.0);
+    app.update();
+    assert!(!passes(&app, flee), "changing the right-hand parameter re-evaluates");
+
+    // Removing an operand's parameter re-evaluates and blocks
+    app.world_mut().get_mut::<FloatParam<MaxHealth>>(root).unwrap().set(100.0);
+    app.update();
+    assert!(passes(&app, flee));
+    app.world_mut().entity_mut(root).remove::<FloatParam<Health>>();
+    app.update();
+    assert!(!passes(&app, flee), "missing parameter blocks");
+}
+
+#[test]
+fn param_guards_track_param_removal_pruned_edges_and_hierarchy_moves() {
+    let mut app = test_app();
+    app.add_systems(Update, apply_bool_param_guards::<PB>);
+
+    let root = app.world_mut().spawn(BoolParam::<PB>::new(true)).id();
+    let sub = app.world_mut().spawn((StateChildOf(root), BoolParam::<PB>::new(false))).id();
+    let other = app.world_mut().spawn(StateChildOf(root)).id();
+    let s = app.world_mut().spawn(StateChildOf(root)).id();
+    let t = app.world_mut().spawn(StateChildOf(root)).id();
+    let edge = app.world_mut().spawn((Source(s), Target(t), BoolEquals::<PB>::new(true))).id();
+    let nearest = app.world_mut().spawn((Source(s), Target(t), BoolEquals::<PB>::new(true), ParamScope::NearestAncestor)).id();
+
+    let blocked = |app: &App, e: Entity| !app.world().get::<Guards>(e).map(|g| g.check()).unwrap_or(true);
+    app.update();
+    assert!(!blocked(&app, edge));
+    assert!(!blocked(&app, nearest), "resolves to the root");
+
+    // Removing the param re-blocks the edge
+    app.world_mut().entity_mut(root).remove::<BoolParam<PB>>();
+    app.update();
+    assert!(blocked(&app, edge), "missing param blocks");
+    app.world_mut().entity_mut(root).insert(BoolParam::<PB>::new(true));
+    app.update();
+    assert!(!blocked(&app, edge), "re-added param unblocks");
+
+    // Moving the source under the sub-chart re-resolves the nearest holder
+    app.world_mut().entity_mut(s).insert(StateChildOf(sub));
+    app.update();
+    assert!(blocked(&app, nearest), "sub-chart override is false");
+    assert!(app.world().get::<ParamDependents<PB, bool>>(sub).unwrap().edges().any(|e| e == nearest));
+
+    // Moving it away again drops the stale index entry
+    app.world_mut().entity_mut(s).insert(StateChildOf(other));
+    app.update();
+    assert!(!blocked(&app, nearest), "back to the root value");
+    assert!(!app.world().get::<ParamDependents<PB, bool>>(sub).unwrap().edges().any(|e| e == nearest));
+
+    // Despawned edges are pruned from the index
+    app.world_mut().entity_mut(edge).despawn();
+    app.update();
+    assert!(!app.world().get::<ParamDependents<PB, bool>>(root).unwrap().edges().any(|e| e == edge));
+
+    // Edges losing their condition have the guard lifted
+    app.world_mut().entity_mut(root).get_mut::<BoolParam<PB>>().unwrap().set(false);
+    app.update();
+    assert!(blocked(&app, nearest));
+    app.world_mut().entity_mut(nearest).remove::<BoolEquals<PB>>();
+    app.update();
+    assert!(!blocked(&app, nearest), "guard lifted with the condition");
+}
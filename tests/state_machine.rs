@@ -1,7 +1,7 @@
 use std::time::Duration;
 
-use bevy::prelude::*;
-use bevy_gearbox::{prelude::*, transitions::{After, ResetEdge, ResetScope}, GearboxPlugin};
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use bevy_gearbox::{prelude::*, transitions::{After, EdgeTimer, ResetEdge, ResetScope}, GearboxPlugin};
 
 fn test_app() -> App {
     let mut app = App::new();
@@ -322,6 +322,24 @@ fn state_component_adds_on_enter_removes_on_exit() {
     assert!(app.world().get::<Foo>(root).is_none());
 }
 
+#[derive(Component, Clone, PartialEq, Eq, Debug)]
+struct AutoFoo(i32);
+
+register_state_component!(AutoFoo);
+
+#[test]
+fn state_component_registered_through_inventory() {
+    let mut app = test_app();
+
+    let root = app.world_mut().spawn_empty().id();
+    let s = app.world_mut().spawn((StateComponent(AutoFoo(3)),)).id();
+    app.world_mut().entity_mut(s).insert(StateChildOf(root));
+    app.world_mut().entity_mut(root).insert((InitialState(s), StateMachine::new()));
+    app.update();
+
+    assert_eq!(app.world().get::<AutoFoo>(root).cloned(), Some(AutoFoo(3)));
+}
+
 #[test]
 fn transitions_external_vs_internal_lca_reentry() {
     let mut app = test_app();
@@ -893,4 +911,1058 @@ fn event_after_timer_handles_missing_target_during_delay() {
     let sm = app.world().get::<StateMachine>(root).unwrap();
     assert!(sm.active_leaves.contains(&s), "should remain on S when target is missing");
     assert!(!sm.active_leaves.contains(&t), "should not transition when target is missing");
-}
\ No newline at end of file
+}
+#[derive(bevy::ecs::schedule::ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct ChartTick;
+
+#[test]
+fn after_timers_use_configured_schedule_clock_and_time_scale() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(AssetPlugin::default());
+    app.insert_resource(TimerSettings::new(ChartTick, TimerClock::Custom));
+    app.add_plugins(GearboxPlugin);
+    app.init_schedule(ChartTick);
+
+    let spawn_machine = |app: &mut App, scale: f32| -> (Entity, Entity) {
+        let root = app.world_mut().spawn((ChartClock { delta: Duration::from_millis(30) }, TimeScale(scale))).id();
+        let s = app.world_mut().spawn(StateChildOf(root)).id();
+        let t = app.world_mut().spawn(StateChildOf(root)).id();
+        app.world_mut().spawn((Source(s), Target(t), AlwaysEdge, After::new(Duration::from_millis(50))));
+        app.world_mut().entity_mut(root).insert((InitialState(s), StateMachine::new()));
+        (root, t)
+    };
+    let (hasted, hasted_target) = spawn_machine(&mut app, 2.0);
+    let (normal, normal_target) = spawn_machine(&mut app, 1.0);
+    let in_target = |app: &App, root: Entity, target: Entity| {
+        app.world().get::<StateMachine>(root).unwrap().active_leaves.contains(&target)
+    };
+
+    // Update no longer ticks timers, regardless of elapsed time
+    app.update();
+    std::thread::sleep(Duration::from_millis(60));
+    app.update();
+    assert!(!in_target(&app, hasted, hasted_target));
+
+    // 30ms * 2 = 60ms per tick for the hasted machine, 30ms for the normal one
+    app.world_mut().run_schedule(ChartTick);
+    app.update();
+    assert!(in_target(&app, hasted, hasted_target), "hasted timer fires on the first tick");
+    assert!(!in_target(&app, normal, normal_target));
+
+    app.world_mut().run_schedule(ChartTick);
+    app.update();
+    assert!(in_target(&app, normal, normal_target), "normal timer fires on the second tick");
+}
+
+#[derive(Resource, Default)]
+struct Pews(u32);
+
+#[test]
+fn every_fires_repeatedly_up_to_max_repetitions() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(AssetPlugin::default());
+    app.insert_resource(TimerSettings::new(Update, TimerClock::Custom));
+    app.add_plugins(GearboxPlugin);
+    app.init_resource::<Pews>();
+
+    let root = app.world_mut().spawn(ChartClock { delta: Duration::from_millis(30) }).id();
+    let repeating = app.world_mut().spawn(StateChildOf(root)).id();
+    let edge = app.world_mut().spawn((
+        Source(repeating),
+        Target(repeating),
+        EdgeKind::Internal,
+        Every::new(Duration::from_millis(30)).with_max_repetitions(3),
+    )).id();
+    app.world_mut().entity_mut(edge).observe(|_: On<TransitionActions>, mut pews: ResMut<Pews>| pews.0 += 1);
+    app.world_mut().entity_mut(root).insert((InitialState(repeating), StateMachine::new()));
+
+    app.update();
+    assert_eq!(app.world().resource::<Pews>().0, 1, "fires once per interval");
+
+    for _ in 0..5 { app.update(); }
+    assert_eq!(app.world().resource::<Pews>().0, 3, "stops at max repetitions");
+    assert_eq!(app.world().get::<EveryTimer>(edge).unwrap().repetitions(), 3);
+    assert!(app.world().get::<StateMachine>(root).unwrap().active_leaves.contains(&repeating));
+}
+
+struct AttackSpeed;
+
+#[test]
+fn after_delays_sampled_from_range_or_read_from_param() {
+    let sampled_delay = |seed: u64| -> Duration {
+        let mut app = test_app();
+        let root = app.world_mut().spawn(ChartRng::from_seed(seed)).id();
+        let s = app.world_mut().spawn(StateChildOf(root)).id();
+        let t = app.world_mut().spawn(StateChildOf(root)).id();
+        let edge = app.world_mut().spawn((Source(s), Target(t), AlwaysEdge, After::between(1.0, 3.0))).id();
+        app.world_mut().entity_mut(root).insert((InitialState(s), StateMachine::new()));
+        app.update();
+        app.world().get::<EdgeTimer>(edge).unwrap().0.duration()
+    };
+    let first = sampled_delay(7);
+    assert!((Duration::from_secs(1)..=Duration::from_secs(3)).contains(&first));
+    assert_eq!(first, sampled_delay(7), "same seed, same delay");
+
+    let mut app = test_app();
+    let root = app.world_mut().spawn(FloatParam::<AttackSpeed>::new(0.25)).id();
+    let s = app.world_mut().spawn(StateChildOf(root)).id();
+    let t = app.world_mut().spawn(StateChildOf(root)).id();
+    let edge = app.world_mut().spawn((Source(s), Target(t), AlwaysEdge, After::from_param::<AttackSpeed>(Duration::from_secs(1)))).id();
+    app.world_mut().entity_mut(root).insert((InitialState(s), StateMachine::new()));
+    app.update();
+    assert_eq!(app.world().get::<EdgeTimer>(edge).unwrap().0.duration(), Duration::from_millis(250));
+
+    // The parameter is read again when the source is re-entered
+    app.world_mut().get_mut::<FloatParam<AttackSpeed>>(root).unwrap().set(0.5);
+    app.world_mut().commands().trigger(ResetRegion::new(root));
+    app.update();
+    assert_eq!(app.world().get::<EdgeTimer>(edge).unwrap().0.duration(), Duration::from_millis(500));
+
+    // Without the parameter the fallback applies instead of firing at once
+    app.world_mut().entity_mut(root).remove::<FloatParam<AttackSpeed>>();
+    app.world_mut().commands().trigger(ResetRegion::new(root));
+    app.update();
+    assert_eq!(app.world().get::<EdgeTimer>(edge).unwrap().0.duration(), Duration::from_secs(1));
+    assert!(app.world().get::<StateMachine>(root).unwrap().active_leaves.contains(&s), "cooldown still pending");
+}
+
+#[test]
+#[should_panic(expected = "0 <= min <= max")]
+fn after_between_rejects_negative_bounds() {
+    let _ = After::between(-1.0, 2.0);
+}
+
+#[test]
+#[should_panic(expected = "0 <= min <= max")]
+fn after_between_rejects_inverted_bounds() {
+    let _ = After::between(3.0, 1.0);
+}
+
+#[test]
+fn chart_timers_report_remaining_time_for_delayed_and_pending_edges() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(AssetPlugin::default());
+    app.insert_resource(TimerSettings::new(Update, TimerClock::Custom));
+    app.add_plugins(GearboxPlugin);
+
+    let root = app.world_mut().spawn(ChartClock { delta: Duration::from_millis(250) }).id();
+    let s = app.world_mut().spawn(StateChildOf(root)).id();
+    let t = app.world_mut().spawn(StateChildOf(root)).id();
+    let cooldown = app.world_mut().spawn((Source(s), Target(t), AlwaysEdge, After::from_f32(2.0))).id();
+    let cast = app.world_mut().spawn((Source(s), Target(t), EventEdge::<EvtDelayed>::default(), After::from_f32(1.0))).id();
+    app.world_mut().entity_mut(root).insert((InitialState(s), StateMachine::new()));
+
+    let timers = |app: &mut App, entity: Entity| {
+        app.world_mut()
+            .run_system_once(move |timers: ChartTimers| (timers.state(entity), timers.machine(root)))
+            .unwrap()
+    };
+    let (state_timers, machine_timers) = timers(&mut app, s);
+    assert_eq!(state_timers.len(), 1, "no pending event yet");
+    assert_eq!(machine_timers.len(), 1);
+
+    app.world_mut().commands().trigger(EvtDelayed { target: root });
+    app.update();
+    app.update();
+
+    let (state_timers, _) = timers(&mut app, s);
+    let cooldown_timer = state_timers.iter().find(|t| t.edge == cooldown).unwrap();
+    assert_eq!(cooldown_timer.kind, TimedEdgeKind::Delayed);
+    assert_eq!(cooldown_timer.remaining(), Duration::from_millis(1500));
+    assert_eq!(cooldown_timer.fraction(), 0.25);
+    let cast_timer = state_timers.iter().find(|t| t.edge == cast).unwrap();
+    assert_eq!(cast_timer.kind, TimedEdgeKind::PendingEvent);
+    assert_eq!(cast_timer.target, Some(t));
+    assert!(cast_timer.remaining() < Duration::from_secs(1));
+
+    let (inactive_timers, _) = timers(&mut app, t);
+    assert!(inactive_timers.is_empty());
+}
+
+#[test]
+fn paused_machine_freezes_timers_and_queues_or_drops_events() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(AssetPlugin::default());
+    app.insert_resource(TimerSettings::new(Update, TimerClock::Custom));
+    app.add_plugins(GearboxPlugin);
+
+    // s --(EvtDelayed)--> t --(After 100ms)--> u --(Always, guarded)--> v
+    let root = app.world_mut().spawn(ChartClock { delta: Duration::from_millis(100) }).id();
+    let s = app.world_mut().spawn(StateChildOf(root)).id();
+    let t = app.world_mut().spawn(StateChildOf(root)).id();
+    let u = app.world_mut().spawn(StateChildOf(root)).id();
+    let v = app.world_mut().spawn(StateChildOf(root)).id();
+    app.world_mut().spawn((Source(s), Target(t), EventEdge::<EvtDelayed>::default()));
+    app.world_mut().spawn((Source(t), Target(u), AlwaysEdge, After::from_f32(0.1)));
+    let gated = app.world_mut().spawn((Source(u), Target(v), AlwaysEdge, Guards::init(["gate"]))).id();
+    app.world_mut().entity_mut(root).insert((InitialState(s), StateMachine::new()));
+    app.update();
+    let in_state = |app: &App, state: Entity| app.world().get::<StateMachine>(root).unwrap().active_leaves.contains(&state);
+
+    // Dropped while paused with PausedEvents::Drop
+    app.world_mut().entity_mut(root).insert(Paused::drop_events());
+    app.world_mut().commands().trigger(EvtDelayed { target: root });
+    app.update();
+    app.world_mut().entity_mut(root).remove::<Paused>();
+    app.update();
+    assert!(in_state(&app, s), "dropped event is never processed");
+
+    // Queued while paused, processed on resume
+    app.world_mut().entity_mut(root).insert(Paused::queue());
+    app.world_mut().commands().trigger(EvtDelayed { target: root });
+    app.update();
+    assert!(in_state(&app, s));
+    assert_eq!(app.world().get::<Paused>(root).unwrap().queued_len(), 1);
+    app.world_mut().entity_mut(root).remove::<Paused>();
+    app.update();
+    assert!(in_state(&app, t), "queued event is replayed on resume");
+
+    // Timers don't tick while paused
+    app.world_mut().entity_mut(root).insert(Paused::queue());
+    for _ in 0..3 { app.update(); }
+    assert!(in_state(&app, t), "After timer is frozen");
+    app.world_mut().entity_mut(root).remove::<Paused>();
+    app.update();
+    app.update();
+    assert!(in_state(&app, u));
+
+    // Guard changes while paused are re-checked on resume
+    app.world_mut().entity_mut(root).insert(Paused::queue());
+    app.world_mut().get_mut::<Guards>(gated).unwrap().remove_guard("gate");
+    app.update();
+    assert!(in_state(&app, u), "Always edges are suspended");
+    app.world_mut().entity_mut(root).remove::<Paused>();
+    app.update();
+    assert!(in_state(&app, v));
+}
+
+#[test]
+fn paused_after_timer_resumes_with_remaining_time() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(AssetPlugin::default());
+    app.insert_resource(TimerSettings::new(Update, TimerClock::Custom));
+    app.add_plugins(GearboxPlugin);
+
+    // s --(After 300ms, guarded)--> t
+    let root = app.world_mut().spawn(ChartClock { delta: Duration::from_millis(100) }).id();
+    let s = app.world_mut().spawn(StateChildOf(root)).id();
+    let t = app.world_mut().spawn(StateChildOf(root)).id();
+    let edge = app.world_mut().spawn((Source(s), Target(t), AlwaysEdge, After::from_f32(0.3), Guards::default())).id();
+    app.world_mut().entity_mut(root).insert((InitialState(s), StateMachine::new()));
+    app.update();
+    app.update();
+    let remaining = |app: &App| app.world().get::<EdgeTimer>(edge).unwrap().0.remaining();
+    let before = remaining(&app);
+    assert!(before < Duration::from_millis(300), "timer has been ticking");
+
+    // Pause mid-delay; the guard is touched while paused
+    app.world_mut().entity_mut(root).insert(Paused::queue());
+    app.world_mut().get_mut::<Guards>(edge).unwrap().set_changed();
+    for _ in 0..3 { app.update(); }
+    app.world_mut().entity_mut(root).remove::<Paused>();
+    app.world_mut().flush();
+    assert_eq!(remaining(&app), before, "resume keeps the time left");
+    app.update();
+    assert_eq!(remaining(&app), before - Duration::from_millis(100), "and keeps ticking from there");
+}
+
+#[derive(SimpleTransition, EntityEvent, Clone)]
+struct Knock { #[event_target] target: Entity, n: u32 }
+
+#[test]
+fn ancestor_deferral_queues_with_capacity_and_replays_in_order_after_exit() {
+    let mut app = test_app();
+
+    // root -> { P (defers Knock) -> { L, L2 }, Q, R, S }
+    let root = app.world_mut().spawn_empty().id();
+    let p = app.world_mut().spawn((StateChildOf(root), DeferEvent::<Knock>::with_capacity(2, DeferOverflow::DropOldest))).id();
+    let l = app.world_mut().spawn(StateChildOf(p)).id();
+    let l2 = app.world_mut().spawn(StateChildOf(p)).id();
+    let q = app.world_mut().spawn(StateChildOf(root)).id();
+    let r = app.world_mut().spawn(StateChildOf(root)).id();
+    let s = app.world_mut().spawn(StateChildOf(root)).id();
+    app.world_mut().entity_mut(p).insert(InitialState(l));
+    app.world_mut().spawn((Source(l), Target(l2), EventEdge::<Knock>::default()));
+    app.world_mut().spawn((Source(p), Target(q), EventEdge::<EvtDelayed>::default()));
+    app.world_mut().spawn((Source(q), Target(r), EventEdge::<Knock>::default()));
+    app.world_mut().spawn((Source(r), Target(s), EventEdge::<Knock>::default()));
+    app.world_mut().entity_mut(root).insert((InitialState(p), StateMachine::new()));
+    app.update();
+
+    for n in 1..=3 {
+        app.world_mut().commands().trigger(Knock { target: root, n });
+    }
+    app.update();
+    let leaves = |app: &App| app.world().get::<StateMachine>(root).unwrap().active_leaves.clone();
+    assert!(leaves(&app).contains(&l), "deferral on P covers its descendant L");
+    let queued: Vec<u32> = app.world().get::<DeferEvent<Knock>>(p).unwrap().deferred.iter().map(|k| k.n).collect();
+    assert_eq!(queued, vec![2, 3], "oldest dropped at capacity");
+
+    // Leaving P replays both knocks once Q is active: Q -> R -> S
+    app.world_mut().commands().trigger(EvtDelayed { target: root });
+    app.update();
+    assert!(leaves(&app).contains(&s), "both deferred events handled by the new configuration");
+    assert!(app.world().get::<DeferEvent<Knock>>(p).unwrap().deferred.is_empty());
+}
+
+#[test]
+fn ancestor_edge_consuming_event_skips_deferral() {
+    let mut app = test_app();
+
+    // root -> { Alive -> { Casting (defers Knock) }, Stunned, Dazed }
+    // Alive -> Stunned and Stunned -> Dazed on Knock
+    let root = app.world_mut().spawn_empty().id();
+    let alive = app.world_mut().spawn(StateChildOf(root)).id();
+    let casting = app.world_mut().spawn((StateChildOf(alive), DeferEvent::<Knock>::new())).id();
+    let stunned = app.world_mut().spawn(StateChildOf(root)).id();
+    let dazed = app.world_mut().spawn(StateChildOf(root)).id();
+    app.world_mut().entity_mut(alive).insert(InitialState(casting));
+    app.world_mut().spawn((Source(alive), Target(stunned), EventEdge::<Knock>::default()));
+    app.world_mut().spawn((Source(stunned), Target(dazed), EventEdge::<Knock>::default()));
+    app.world_mut().entity_mut(root).insert((InitialState(alive), StateMachine::new()));
+    app.update();
+
+    app.world_mut().commands().trigger(Knock { target: root, n: 1 });
+    app.update();
+    app.update();
+    let leaves = app.world().get::<StateMachine>(root).unwrap().active_leaves.clone();
+    assert!(leaves.contains(&stunned), "the ancestor edge handles the event");
+    assert!(!leaves.contains(&dazed), "the event is not replayed after being consumed");
+    assert!(app.world().get::<DeferEvent<Knock>>(casting).unwrap().deferred.is_empty());
+}
+
+#[test]
+fn parallel_regions_deferring_same_event_queue_it_once() {
+    let mut app = test_app();
+
+    // root -> { Par (parallel) -> { A (defers Knock) -> A1, B (defers Knock) -> B1 }, Done, D2, D3 }
+    let root = app.world_mut().spawn_empty().id();
+    let par = app.world_mut().spawn((StateChildOf(root), Parallel)).id();
+    let a = app.world_mut().spawn((StateChildOf(par), DeferEvent::<Knock>::new())).id();
+    let a1 = app.world_mut().spawn(StateChildOf(a)).id();
+    let b = app.world_mut().spawn((StateChildOf(par), DeferEvent::<Knock>::new())).id();
+    let b1 = app.world_mut().spawn(StateChildOf(b)).id();
+    let done = app.world_mut().spawn(StateChildOf(root)).id();
+    let d2 = app.world_mut().spawn(StateChildOf(root)).id();
+    let d3 = app.world_mut().spawn(StateChildOf(root)).id();
+    app.world_mut().entity_mut(a).insert(InitialState(a1));
+    app.world_mut().entity_mut(b).insert(InitialState(b1));
+    app.world_mut().spawn((Source(par), Target(done), EventEdge::<EvtDelayed>::default()));
+    app.world_mut().spawn((Source(done), Target(d2), EventEdge::<Knock>::default()));
+    app.world_mut().spawn((Source(d2), Target(d3), EventEdge::<Knock>::default()));
+    app.world_mut().entity_mut(root).insert((InitialState(par), StateMachine::new()));
+    app.update();
+
+    app.world_mut().commands().trigger(Knock { target: root, n: 1 });
+    app.update();
+    let queued = |app: &App, s: Entity| app.world().get::<DeferEvent<Knock>>(s).unwrap().deferred.len();
+    assert_eq!(queued(&app, a) + queued(&app, b), 1, "one copy per machine");
+
+    // Leaving Par replays the knock exactly once: Done -> D2, not on to D3
+    app.world_mut().commands().trigger(EvtDelayed { target: root });
+    app.update();
+    let leaves = app.world().get::<StateMachine>(root).unwrap().active_leaves.clone();
+    assert!(leaves.contains(&d2));
+    assert!(!leaves.contains(&d3));
+}
+
+fn spawn_worker(world: &mut World) -> Entity {
+    // worker -> { Working, Done(final) }, Working -> Done on Knock
+    let worker = world.spawn(Name::new("worker")).id();
+    let working = world.spawn(StateChildOf(worker)).id();
+    let done = world.spawn((StateChildOf(worker), FinalState)).id();
+    world.spawn((Source(working), Target(done), EventEdge::<Knock>::default()));
+    world.entity_mut(worker).insert(InitialState(working));
+    worker
+}
+
+#[test]
+fn invoke_spawns_child_machine_forwards_events_and_completes_parent_state() {
+    let mut app = test_app();
+
+    let root = app.world_mut().spawn_empty().id();
+    let fetching = app.world_mut().spawn((StateChildOf(root), Invoke::new(spawn_worker), ForwardEvents::<Knock>::default())).id();
+    let idle = app.world_mut().spawn(StateChildOf(root)).id();
+    app.world_mut().spawn((Source(fetching), Target(idle), EventEdge::<InvokeDone>::default()));
+    app.world_mut().entity_mut(root).insert((InitialState(fetching), StateMachine::new()));
+    app.update();
+
+    let worker = app.world().get::<InvokedMachine>(fetching).expect("worker invoked on enter").0;
+    assert_eq!(app.world().get::<InvokedBy>(worker).unwrap().0, fetching);
+    assert!(app.world().get::<StateMachine>(worker).is_some(), "invoked machine is started");
+
+    // Knock on the parent machine is forwarded; the worker reaches its final state
+    app.world_mut().commands().trigger(Knock { target: root, n: 1 });
+    app.update();
+    let leaves = app.world().get::<StateMachine>(root).unwrap().active_leaves.clone();
+    assert!(leaves.contains(&idle), "InvokeDone moves the invoking state on");
+    assert!(app.world().get_entity(worker).is_err(), "worker despawned when fetching exits");
+    assert!(app.world().get::<InvokedMachine>(fetching).is_none());
+}
+
+#[derive(Component, Clone, PartialEq, Eq, Debug)]
+struct Shielded;
+
+fn spawn_shielded_worker(world: &mut World) -> Entity {
+    // worker -> Working (StateComponent(Shielded))
+    let worker = world.spawn_empty().id();
+    let working = world.spawn((StateChildOf(worker), StateComponent(Shielded))).id();
+    world.entity_mut(worker).insert(InitialState(working));
+    worker
+}
+
+#[test]
+fn stopping_invoked_machine_exits_its_states() {
+    let mut app = test_app();
+    app.add_state_component::<Shielded>();
+
+    let root = app.world_mut().spawn_empty().id();
+    let fetching = app.world_mut().spawn((StateChildOf(root), Invoke::new(spawn_shielded_worker).keep_on_exit())).id();
+    let idle = app.world_mut().spawn(StateChildOf(root)).id();
+    app.world_mut().spawn((Source(fetching), Target(idle), EventEdge::<TestEvt>::default()));
+    app.world_mut().spawn((Source(idle), Target(fetching), EventEdge::<EvtGoBack>::default()));
+    app.world_mut().entity_mut(root).insert((InitialState(fetching), StateMachine::new()));
+    app.update();
+    let worker = app.world().get::<InvokedMachine>(fetching).unwrap().0;
+    assert!(app.world().get::<Shielded>(worker).is_some());
+
+    // Stop runs the worker's exits, so its StateComponent is withdrawn
+    app.world_mut().commands().trigger(TestEvt { target: root });
+    app.update();
+    assert!(app.world().get::<Shielded>(worker).is_none());
+    assert!(app.world().get::<StateMachine>(worker).is_none());
+
+    // Re-entering restarts the kept machine
+    app.world_mut().commands().trigger(EvtGoBack { target: root });
+    app.update();
+    assert!(app.world().get::<Shielded>(worker).is_some());
+}
+
+#[test]
+fn paused_machine_forwards_events_only_on_resume() {
+    let mut app = test_app();
+
+    let root = app.world_mut().spawn_empty().id();
+    let fetching = app.world_mut().spawn((StateChildOf(root), Invoke::new(spawn_worker), ForwardEvents::<Knock>::default())).id();
+    let idle = app.world_mut().spawn(StateChildOf(root)).id();
+    app.world_mut().spawn((Source(fetching), Target(idle), EventEdge::<InvokeDone>::default()));
+    app.world_mut().entity_mut(root).insert((InitialState(fetching), StateMachine::new()));
+    app.update();
+    let worker = app.world().get::<InvokedMachine>(fetching).unwrap().0;
+
+    app.world_mut().entity_mut(root).insert(Paused::queue());
+    app.world_mut().commands().trigger(Knock { target: root, n: 1 });
+    app.update();
+    let working = app.world().get::<StateMachine>(worker).unwrap().active_leaves.clone();
+    assert!(working.iter().all(|&s| app.world().get::<FinalState>(s).is_none()), "worker doesn't see the knock while paused");
+
+    app.world_mut().entity_mut(root).remove::<Paused>();
+    app.update();
+    let leaves = app.world().get::<StateMachine>(root).unwrap().active_leaves.clone();
+    assert!(leaves.contains(&idle), "the knock is forwarded once on resume");
+}
+
+
+#[derive(Resource, Default)]
+struct Exited(Vec<Entity>);
+
+#[test]
+fn despawning_active_states_repairs_machine_and_strips_references() {
+    let mut app = test_app();
+    app.add_state_component::<Shielded>();
+    app.init_resource::<Exited>();
+    app.add_observer(|exit_state: On<ExitState>, mut exited: ResMut<Exited>| exited.0.push(exit_state.target));
+
+    // root -> P -> { A (initial), B, C (StateComponent) }, with Z -> B elsewhere
+    let root = app.world_mut().spawn_empty().id();
+    let p = app.world_mut().spawn(StateChildOf(root)).id();
+    let a = app.world_mut().spawn(StateChildOf(p)).id();
+    let b = app.world_mut().spawn(StateChildOf(p)).id();
+    let c = app.world_mut().spawn((StateChildOf(p), StateComponent(Shielded))).id();
+    let z = app.world_mut().spawn(StateChildOf(root)).id();
+    let z_to_b = app.world_mut().spawn((Source(z), Target(b))).id();
+    app.world_mut().spawn((Source(a), Target(b), EventEdge::<TestEvt>::default()));
+    app.world_mut().entity_mut(p).insert(InitialState(a));
+    app.world_mut().entity_mut(root).insert((InitialState(p), StateMachine::new()));
+    app.update();
+    app.world_mut().commands().trigger(TestEvt { target: root });
+    app.update();
+    let leaves = |app: &App| app.world().get::<StateMachine>(root).unwrap().active_leaves.clone();
+    assert!(leaves(&app).contains(&b));
+
+    // Plain despawn of the active leaf: exited, P falls back to A, dangling Target stripped
+    app.world_mut().resource_mut::<Exited>().0.clear();
+    app.world_mut().despawn(b);
+    app.update();
+    assert_eq!(app.world().resource::<Exited>().0, vec![b]);
+    assert!(leaves(&app).contains(&a) && !leaves(&app).contains(&b));
+    assert!(app.world().get::<Target>(z_to_b).is_none());
+
+    // despawn_state exits while the state still exists, so its StateComponent is removed
+    app.world_mut().entity_mut(p).insert(InitialState(c));
+    app.world_mut().commands().trigger(ResetRegion::new(root));
+    app.update();
+    assert!(leaves(&app).contains(&c) && app.world().get::<Shielded>(root).is_some());
+    app.world_mut().commands().entity(c).despawn_state();
+    app.update();
+    assert!(app.world().get::<Shielded>(root).is_none());
+    assert!(app.world().get::<InitialState>(p).is_none(), "stale InitialState stripped");
+    assert!(leaves(&app).contains(&p), "P without an initial state becomes the leaf");
+
+    // despawn_state on an entity despawned earlier in the same flush is a no-op
+    let loose = app.world_mut().spawn_empty().id();
+    let mut commands = app.world_mut().commands();
+    commands.entity(loose).despawn();
+    commands.entity(loose).despawn_state();
+    app.update();
+    assert!(app.world().get_entity(loose).is_err());
+}
+
+#[test]
+fn despawns_in_one_flush_are_repaired_together() {
+    let mut app = test_app();
+
+    // root -> Par (parallel) -> { L -> { L1 (initial), L2 }, R -> { R1 (initial), R2 } }
+    let root = app.world_mut().spawn_empty().id();
+    let par = app.world_mut().spawn((StateChildOf(root), Parallel)).id();
+    let l = app.world_mut().spawn(StateChildOf(par)).id();
+    let l1 = app.world_mut().spawn(StateChildOf(l)).id();
+    let l2 = app.world_mut().spawn(StateChildOf(l)).id();
+    let r = app.world_mut().spawn(StateChildOf(par)).id();
+    let r1 = app.world_mut().spawn(StateChildOf(r)).id();
+    let r2 = app.world_mut().spawn(StateChildOf(r)).id();
+    app.world_mut().entity_mut(l).insert(InitialState(l1));
+    app.world_mut().entity_mut(r).insert(InitialState(r1));
+    app.world_mut().spawn((Source(l1), Target(l2), EventEdge::<TestEvt>::default()));
+    app.world_mut().spawn((Source(r1), Target(r2), EventEdge::<TestEvt>::default()));
+    app.world_mut().entity_mut(root).insert((InitialState(par), StateMachine::new()));
+
+    // A second machine, despawned whole in the same flush
+    let other = app.world_mut().spawn_empty().id();
+    let other_a = app.world_mut().spawn(StateChildOf(other)).id();
+    app.world_mut().entity_mut(other).insert((InitialState(other_a), StateMachine::new()));
+    app.update();
+    app.world_mut().commands().trigger(TestEvt { target: root });
+    app.update();
+
+    let mut commands = app.world_mut().commands();
+    commands.entity(l2).despawn();
+    commands.entity(r2).despawn();
+    commands.entity(other).despawn();
+    app.update();
+
+    let leaves = app.world().get::<StateMachine>(root).unwrap().active_leaves.clone();
+    assert!(leaves.contains(&l1) && leaves.contains(&r1), "both regions fall back to their initial states");
+    assert_eq!(leaves.len(), 2);
+    assert!(app.world().get_entity(other_a).is_err());
+}
+
+#[test]
+fn runtime_edits_skip_entities_despawned_in_the_same_flush() {
+    let mut app = test_app();
+
+    // root -> { A (initial), B }, A -> B on TestEvt
+    let root = app.world_mut().spawn_empty().id();
+    let a = app.world_mut().spawn(StateChildOf(root)).id();
+    let b = app.world_mut().spawn(StateChildOf(root)).id();
+    let edge = app.world_mut().spawn((Source(a), Target(b), EventEdge::<TestEvt>::default())).id();
+    app.world_mut().entity_mut(root).insert((InitialState(a), StateMachine::new()));
+    app.update();
+
+    let mut commands = app.world_mut().commands();
+    commands.entity(b).despawn();
+    commands.entity(edge).despawn();
+    commands.reparent_state(b, a);
+    commands.set_initial_state(b, a);
+    commands.set_target(edge, a);
+    commands.set_parallel(b, true);
+    app.update();
+
+    let leaves = app.world().get::<StateMachine>(root).unwrap().active_leaves.clone();
+    assert!(leaves.contains(&a), "edits to despawned entities are skipped");
+}
+
+#[test]
+fn runtime_edits_keep_running_machine_consistent() {
+    let mut app = test_app();
+
+    // root -> Alive -> Idle
+    let root = app.world_mut().spawn_empty().id();
+    let alive = app.world_mut().spawn(StateChildOf(root)).id();
+    let idle = app.world_mut().spawn(StateChildOf(alive)).id();
+    app.world_mut().entity_mut(alive).insert(InitialState(idle));
+    app.world_mut().entity_mut(root).insert((InitialState(alive), StateMachine::new()));
+    app.update();
+    let leaves = |app: &App| app.world().get::<StateMachine>(root).unwrap().active_leaves.iter().copied().collect::<std::collections::HashSet<_>>();
+
+    // New inactive region Skills -> { Charge (initial), Fire } under the exclusive Alive
+    let mut commands = app.world_mut().commands();
+    let skills = commands.add_state(alive, ());
+    let charge = commands.add_state(skills, ());
+    let fire = commands.add_state(skills, ());
+    commands.set_initial_state(skills, charge);
+    app.update();
+    assert_eq!(leaves(&app), [idle].into());
+
+    // Turning Alive parallel enters the new region
+    app.world_mut().commands().set_parallel(alive, true);
+    app.update();
+    assert_eq!(leaves(&app), [idle, charge].into());
+
+    // An Always edge added to an active state fires right away
+    app.world_mut().commands().add_edge(charge, (Target(fire), AlwaysEdge));
+    app.update();
+    assert_eq!(leaves(&app), [idle, fire].into());
+
+    // Grafting a detached sub-chart under the parallel Alive enters it
+    let graft = app.world_mut().spawn_empty().id();
+    let g1 = app.world_mut().spawn(StateChildOf(graft)).id();
+    app.world_mut().entity_mut(graft).insert(InitialState(g1));
+    app.world_mut().commands().reparent_state(graft, alive);
+    app.update();
+    assert_eq!(leaves(&app), [idle, fire, g1].into());
+
+    // Back to exclusive: only the initial region stays
+    app.world_mut().commands().set_parallel(alive, false);
+    app.update();
+    assert_eq!(leaves(&app), [idle].into());
+    let machine = app.world().get::<StateMachine>(root).unwrap();
+    assert!(!machine.is_active(&skills) && !machine.is_active(&graft));
+}
+
+#[test]
+fn state_paths_resolve_authoring_and_describe_active_states() {
+    let mut app = test_app();
+
+    // root -> Alive -> { Locomotion -> { Standing, Running }, Dead, <unnamed> }
+    let root = app.world_mut().spawn(InitialStatePath("Alive".into())).id();
+    let alive = app.world_mut().spawn((Name::new("Alive"), StateChildOf(root), InitialStatePath("Alive/Locomotion".into()))).id();
+    let locomotion = app.world_mut().spawn((Name::new("Locomotion"), StateChildOf(alive), InitialStatePath("Alive/Locomotion/Standing".into()))).id();
+    let standing = app.world_mut().spawn((Name::new("Standing"), StateChildOf(locomotion))).id();
+    let running = app.world_mut().spawn((Name::new("Running"), StateChildOf(locomotion))).id();
+    app.world_mut().spawn((Name::new("Dead"), StateChildOf(alive)));
+    let unnamed = app.world_mut().spawn(StateChildOf(alive)).id();
+    app.world_mut().spawn((Source(standing), TargetPath("/Alive/Locomotion/Running".into()), EventEdge::<TestEvt>::default()));
+    app.world_mut().entity_mut(root).insert(StateMachine::new());
+    app.update();
+
+    let leaves = app.world().get::<StateMachine>(root).unwrap().active_leaves.clone();
+    assert!(leaves.contains(&standing), "InitialStatePaths resolved before the first entry");
+
+    app.world_mut().commands().trigger(TestEvt { target: root });
+    app.update();
+    let (found, unnamed_path, active) = app.world_mut().run_system_once(move |paths: StatePaths| {
+        (
+            [paths.find_state(root, "Alive/Locomotion/Running"), paths.find_state(root, "Alive/#2"), paths.find_state(root, "Alive/Flying")],
+            paths.path_of(unnamed).to_string(),
+            paths.active_leaves(root),
+        )
+    }).unwrap();
+    assert_eq!(found, [Some(running), Some(unnamed), None]);
+    assert_eq!(unnamed_path, "Alive/#2");
+    assert_eq!(active, vec![StatePath::parse("Alive/Locomotion/Running")], "TargetPath resolved to Running");
+}
+
+#[derive(Component)]
+struct AppChart;
+
+#[derive(Component)]
+struct MenuOpen;
+
+#[derive(Resource, Default)]
+struct MenuTicks(u32);
+
+#[test]
+fn chart_states_and_in_chart_state_run_condition() {
+    let mut app = test_app();
+    app.init_resource::<MenuTicks>();
+    app.add_systems(Update, (|mut ticks: ResMut<MenuTicks>| ticks.0 += 1).run_if(in_chart_state::<AppChart, MenuOpen>()));
+
+    // root(AppChart) -> { Playing, Menu(MenuOpen) -> Options }
+    let root = app.world_mut().spawn(AppChart).id();
+    let playing = app.world_mut().spawn(StateChildOf(root)).id();
+    let menu = app.world_mut().spawn((StateChildOf(root), MenuOpen)).id();
+    let options = app.world_mut().spawn(StateChildOf(menu)).id();
+    app.world_mut().entity_mut(menu).insert(InitialState(options));
+    app.world_mut().spawn((Source(playing), Target(menu), EventEdge::<TestEvt>::default()));
+    app.world_mut().entity_mut(root).insert((InitialState(playing), StateMachine::new()));
+    app.update();
+    assert_eq!(app.world().resource::<MenuTicks>().0, 0);
+
+    app.world_mut().commands().trigger(TestEvt { target: root });
+    app.update();
+    app.update();
+    assert!(app.world().resource::<MenuTicks>().0 >= 1, "runs while the chart is in a MenuOpen state");
+
+    // Works next to mutable queries
+    let (in_menu, leaves, with_menu, root_of) = app.world_mut().run_system_once(move |_names: Query<&mut Name>, states: ChartStates| {
+        (states.is_in(root, menu), states.active_leaves(root).collect::<Vec<_>>(), states.active_with::<MenuOpen>(root).collect::<Vec<_>>(), states.root_of(options))
+    }).unwrap();
+    assert!(in_menu);
+    assert_eq!(leaves, vec![options]);
+    assert_eq!(with_menu, vec![menu]);
+    assert_eq!(root_of, root);
+}
+
+#[derive(Component, Clone, PartialEq, Eq, Debug)]
+struct Speed(u32);
+
+#[derive(Component, Clone, PartialEq, Eq, Debug)]
+struct CanJump;
+
+#[test]
+fn overlapping_state_components_layer_by_providers() {
+    let mut app = test_app();
+    app.add_state_component::<Speed>();
+    app.add_state_inactive_component::<CanJump>();
+
+    // root -> { Outer(Speed 1) -> Par -> { Left -> { Fast(Speed 5), Idle }, Right -> { Slow(Speed 3), Idle2 } }, Done }
+    // Fast and Slow also suppress CanJump.
+    let root = app.world_mut().spawn(CanJump).id();
+    let outer = app.world_mut().spawn((StateChildOf(root), StateComponent(Speed(1)))).id();
+    let done = app.world_mut().spawn(StateChildOf(root)).id();
+    let par = app.world_mut().spawn((StateChildOf(outer), Parallel)).id();
+    let left = app.world_mut().spawn(StateChildOf(par)).id();
+    let right = app.world_mut().spawn(StateChildOf(par)).id();
+    let fast = app.world_mut().spawn((StateChildOf(left), StateComponent(Speed(5)), StateInactiveComponent(CanJump))).id();
+    let idle = app.world_mut().spawn(StateChildOf(left)).id();
+    let slow = app.world_mut().spawn((StateChildOf(right), StateComponent(Speed(3)), StateInactiveComponent(CanJump))).id();
+    let idle2 = app.world_mut().spawn(StateChildOf(right)).id();
+    app.world_mut().entity_mut(outer).insert(InitialState(par));
+    app.world_mut().entity_mut(left).insert(InitialState(fast));
+    app.world_mut().entity_mut(right).insert(InitialState(slow));
+    app.world_mut().spawn((Source(fast), Target(idle), EventEdge::<TestEvt>::default()));
+    app.world_mut().spawn((Source(slow), Target(idle2), EventEdge::<EvtGoOut>::default()));
+    app.world_mut().spawn((Source(outer), Target(done), EventEdge::<EvtGoBack>::default()));
+    app.world_mut().entity_mut(root).insert((InitialState(outer), StateMachine::new()));
+    app.update();
+    let speed = |app: &App| app.world().get::<Speed>(root).cloned();
+    let can_jump = |app: &App| app.world().get::<CanJump>(root).is_some();
+    assert!(matches!(speed(&app), Some(Speed(5)) | Some(Speed(3))), "a leaf provider wins over Outer");
+    assert!(!can_jump(&app));
+
+    // Fast exits: Slow still provides Speed and still suppresses CanJump
+    app.world_mut().commands().trigger(TestEvt { target: root });
+    app.update();
+    assert_eq!(speed(&app), Some(Speed(3)));
+    assert!(!can_jump(&app));
+
+    // Slow exits: back to Outer's value, CanJump restored
+    app.world_mut().commands().trigger(EvtGoOut { target: root });
+    app.update();
+    assert_eq!(speed(&app), Some(Speed(1)));
+    assert!(can_jump(&app));
+
+    // Outer exits: no provider left
+    app.world_mut().commands().trigger(EvtGoBack { target: root });
+    app.update();
+    assert_eq!(speed(&app), None);
+    assert!(app.world().get::<ActiveProviders<StateComponent<Speed>>>(root).is_none());
+}
+
+#[test]
+fn older_provider_exit_keeps_runtime_changes_of_newest() {
+    let mut app = test_app();
+    app.add_state_component::<Speed>();
+
+    // root -> Par -> { Left -> { A(Speed 2), A2 }, Right -> { B(Speed 4), B2 } }
+    let root = app.world_mut().spawn_empty().id();
+    let par = app.world_mut().spawn((StateChildOf(root), Parallel)).id();
+    let left = app.world_mut().spawn(StateChildOf(par)).id();
+    let right = app.world_mut().spawn(StateChildOf(par)).id();
+    let a = app.world_mut().spawn((StateChildOf(left), StateComponent(Speed(2)))).id();
+    let a2 = app.world_mut().spawn(StateChildOf(left)).id();
+    let b = app.world_mut().spawn((StateChildOf(right), StateComponent(Speed(4)))).id();
+    let b2 = app.world_mut().spawn(StateChildOf(right)).id();
+    app.world_mut().entity_mut(left).insert(InitialState(a));
+    app.world_mut().entity_mut(right).insert(InitialState(b));
+    app.world_mut().spawn((Source(a), Target(a2), EventEdge::<TestEvt>::default()));
+    app.world_mut().spawn((Source(b), Target(b2), EventEdge::<EvtGoOut>::default()));
+    app.world_mut().entity_mut(root).insert((InitialState(par), StateMachine::new()));
+    app.update();
+
+    let providers = app.world().get::<ActiveProviders<StateComponent<Speed>>>(root).unwrap().providers().to_vec();
+    assert_eq!(providers.len(), 2);
+    let (older, newest) = (providers[0], providers[1]);
+    let newest_value = if newest == a { Speed(2) } else { Speed(4) };
+    assert_eq!(app.world().get::<Speed>(root), Some(&newest_value));
+
+    // The newest provider's value changes at runtime, then the older provider exits
+    app.world_mut().get_mut::<Speed>(root).unwrap().0 = 9;
+    if older == a {
+        app.world_mut().commands().trigger(TestEvt { target: root });
+    } else {
+        app.world_mut().commands().trigger(EvtGoOut { target: root });
+    }
+    app.update();
+    assert_eq!(app.world().get::<Speed>(root), Some(&Speed(9)), "runtime change survives");
+    assert_eq!(app.world().get::<ActiveProviders<StateComponent<Speed>>>(root).unwrap().providers(), &[newest]);
+}
+
+#[derive(Component, Clone)]
+struct Stunned;
+
+#[derive(Component, Clone)]
+struct NoInput;
+
+#[derive(Component)]
+struct Vfx;
+
+#[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+#[reflect(Component)]
+struct Glowing(u32);
+
+#[test]
+fn state_components_apply_to_chosen_targets() {
+    let mut app = test_app();
+    app.register_type::<Glowing>();
+    app.add_state_bundle::<(Stunned, NoInput)>();
+    app.add_state_component::<Speed>();
+
+    // character -> machine root -> vfx; the root's chart is { Idle, Stunned }
+    let character = app.world_mut().spawn_empty().id();
+    let root = app.world_mut().spawn(ChildOf(character)).id();
+    let vfx = app.world_mut().spawn((ChildOf(root), Vfx)).id();
+    let idle = app.world_mut().spawn(StateChildOf(root)).id();
+    let stunned = app.world_mut().spawn((
+        StateChildOf(root),
+        StateComponentTarget::owner(),
+        StateBundle((Stunned, NoInput)),
+        StateReflectComponents::new()
+            .with_on(StateComponentTarget::related::<Children, Vfx>(), Glowing(2))
+            .with_on(StateComponentTarget::state(), Glowing(1)),
+    )).id();
+    let slowed = app.world_mut().spawn((StateChildOf(stunned), StateComponentTarget::state(), StateComponent(Speed(0)))).id();
+    app.world_mut().entity_mut(stunned).insert(InitialState(slowed));
+    app.world_mut().spawn((Source(idle), Target(stunned), EventEdge::<TestEvt>::default()));
+    app.world_mut().spawn((Source(stunned), Target(idle), EventEdge::<EvtGoBack>::default()));
+    app.world_mut().entity_mut(root).insert((InitialState(idle), StateMachine::new()));
+    app.update();
+
+    app.world_mut().commands().trigger(TestEvt { target: root });
+    app.update();
+    assert!(app.world().get::<Stunned>(character).is_some() && app.world().get::<NoInput>(character).is_some());
+    assert!(app.world().get::<Stunned>(root).is_none());
+    assert_eq!(app.world().get::<Glowing>(vfx), Some(&Glowing(2)));
+    assert_eq!(app.world().get::<Glowing>(stunned), Some(&Glowing(1)));
+    assert_eq!(app.world().get::<Speed>(slowed), Some(&Speed(0)));
+    assert!(app.world().get::<Speed>(root).is_none());
+
+    app.world_mut().commands().trigger(EvtGoBack { target: root });
+    app.update();
+    assert!(app.world().get::<Stunned>(character).is_none() && app.world().get::<NoInput>(character).is_none());
+    assert!(app.world().get::<Glowing>(vfx).is_none());
+    assert!(app.world().get::<Glowing>(stunned).is_none());
+    assert!(app.world().get::<Speed>(slowed).is_none());
+}
+
+#[derive(Resource, Clone, PartialEq, Debug)]
+struct MatchRules(u32);
+
+#[test]
+fn state_resources_are_shared_across_machines() {
+    let mut app = test_app();
+    app.add_state_resource::<MatchRules>();
+    app.insert_resource(MatchRules(0));
+
+    // Two machines, each { Lobby (initial), InMatch (provides MatchRules) }
+    let spawn_machine = |app: &mut App, rules: u32| {
+        let root = app.world_mut().spawn_empty().id();
+        let lobby = app.world_mut().spawn(StateChildOf(root)).id();
+        let in_match = app.world_mut().spawn((StateChildOf(root), StateResource(MatchRules(rules)))).id();
+        app.world_mut().spawn((Source(lobby), Target(in_match), EventEdge::<TestEvt>::default()));
+        app.world_mut().spawn((Source(in_match), Target(lobby), EventEdge::<EvtGoBack>::default()));
+        app.world_mut().entity_mut(root).insert((InitialState(lobby), StateMachine::new()));
+        root
+    };
+    let a = spawn_machine(&mut app, 1);
+    let b = spawn_machine(&mut app, 2);
+    app.update();
+    let rules = |app: &App| app.world().get_resource::<MatchRules>().cloned();
+    assert_eq!(rules(&app), Some(MatchRules(0)));
+
+    app.world_mut().commands().trigger(TestEvt { target: a });
+    app.update();
+    assert_eq!(rules(&app), Some(MatchRules(1)));
+
+    app.world_mut().commands().trigger(TestEvt { target: b });
+    app.update();
+    assert_eq!(rules(&app), Some(MatchRules(2)));
+
+    // B leaves: A still provides its rules
+    app.world_mut().commands().trigger(EvtGoBack { target: b });
+    app.update();
+    assert_eq!(rules(&app), Some(MatchRules(1)));
+
+    // A leaves: the value from before the first provider is restored
+    app.world_mut().commands().trigger(EvtGoBack { target: a });
+    app.update();
+    assert_eq!(rules(&app), Some(MatchRules(0)));
+    assert!(app.world().get_resource::<StateResourceProviders<MatchRules>>().is_none());
+}
+
+#[test]
+fn older_state_resource_provider_exit_keeps_runtime_changes() {
+    let mut app = test_app();
+    app.add_state_resource::<MatchRules>();
+
+    let spawn_machine = |app: &mut App, rules: u32| {
+        let root = app.world_mut().spawn_empty().id();
+        let lobby = app.world_mut().spawn(StateChildOf(root)).id();
+        let in_match = app.world_mut().spawn((StateChildOf(root), StateResource(MatchRules(rules)))).id();
+        app.world_mut().spawn((Source(lobby), Target(in_match), EventEdge::<TestEvt>::default()));
+        app.world_mut().spawn((Source(in_match), Target(lobby), EventEdge::<EvtGoBack>::default()));
+        app.world_mut().entity_mut(root).insert((InitialState(lobby), StateMachine::new()));
+        root
+    };
+    let a = spawn_machine(&mut app, 1);
+    let b = spawn_machine(&mut app, 2);
+    app.update();
+    app.world_mut().commands().trigger(TestEvt { target: a });
+    app.update();
+    app.world_mut().commands().trigger(TestEvt { target: b });
+    app.update();
+
+    // B is the newest provider; its value changes at runtime, then the older A leaves
+    app.world_mut().resource_mut::<MatchRules>().0 = 7;
+    app.world_mut().commands().trigger(EvtGoBack { target: a });
+    app.update();
+    assert_eq!(app.world().get_resource::<MatchRules>(), Some(&MatchRules(7)), "runtime change survives");
+
+    // B leaves last: there was no value before, so the resource is removed
+    app.world_mut().commands().trigger(EvtGoBack { target: b });
+    app.update();
+    assert!(app.world().get_resource::<MatchRules>().is_none());
+}
+
+#[derive(Component)]
+struct Attacking;
+
+#[derive(Resource, Default)]
+struct StateSystemLog {
+    ticks: Vec<StateContext>,
+    roots_seen: usize,
+    entered: Vec<Entity>,
+    exited: Vec<Entity>,
+}
+
+#[test]
+fn state_systems_run_per_machine_in_marked_state() {
+    let mut app = test_app();
+    app.init_resource::<StateSystemLog>();
+    app.add_state_systems(Update, Attacking, (
+        |In(context): In<StateContext>, mut log: ResMut<StateSystemLog>| log.ticks.push(context),
+        |In(_): In<StateContext>, mut log: ResMut<StateSystemLog>| log.roots_seen += 1,
+    ));
+    app.add_state_enter_systems(Attacking, |In(context): In<StateContext>, mut log: ResMut<StateSystemLog>| {
+        log.entered.push(context.root);
+    });
+    app.add_state_exit_systems(Attacking, |In(context): In<StateContext>, mut log: ResMut<StateSystemLog>| {
+        log.exited.push(context.root);
+    });
+
+    // Two machines, each { Idle (initial), Attacking }
+    let spawn_machine = |app: &mut App| {
+        let root = app.world_mut().spawn_empty().id();
+        let idle = app.world_mut().spawn(StateChildOf(root)).id();
+        let attacking = app.world_mut().spawn((StateChildOf(root), Attacking)).id();
+        app.world_mut().spawn((Source(idle), Target(attacking), EventEdge::<TestEvt>::default()));
+        app.world_mut().spawn((Source(attacking), Target(idle), EventEdge::<EvtGoBack>::default()));
+        app.world_mut().entity_mut(root).insert((InitialState(idle), StateMachine::new()));
+        (root, attacking)
+    };
+    let (a, a_attacking) = spawn_machine(&mut app);
+    let (b, _) = spawn_machine(&mut app);
+    app.update();
+    assert!(app.world().resource::<StateSystemLog>().ticks.is_empty());
+
+    app.world_mut().commands().trigger(TestEvt { target: a });
+    app.update();
+    {
+        let log = app.world().resource::<StateSystemLog>();
+        assert_eq!(log.entered, vec![a]);
+        assert!(log.ticks.contains(&StateContext { root: a, state: a_attacking }));
+        assert!(log.ticks.iter().all(|context| context.root != b));
+    }
+
+    app.world_mut().commands().trigger(TestEvt { target: b });
+    app.update();
+    app.world_mut().resource_mut::<StateSystemLog>().ticks.clear();
+    app.world_mut().resource_mut::<StateSystemLog>().roots_seen = 0;
+    app.update();
+    {
+        let log = app.world().resource::<StateSystemLog>();
+        assert_eq!(log.ticks.len(), 2, "one run per machine in Attacking");
+        assert_eq!(log.roots_seen, 2, "every system in the tuple runs");
+    }
+
+    app.world_mut().commands().trigger(EvtGoBack { target: a });
+    app.update();
+    app.world_mut().resource_mut::<StateSystemLog>().ticks.clear();
+    app.update();
+    let log = app.world().resource::<StateSystemLog>();
+    assert_eq!(log.exited, vec![a]);
+    assert!(log.ticks.iter().all(|context| context.root == b));
+}
+
+#[test]
+fn state_systems_run_once_per_machine_with_several_marked_states() {
+    let mut app = test_app();
+    app.init_resource::<StateSystemLog>();
+    app.add_state_systems(Update, Attacking, |In(context): In<StateContext>, mut log: ResMut<StateSystemLog>| {
+        log.ticks.push(context);
+    });
+
+    // root -> Outer(Attacking) -> Par (parallel) -> { L(Attacking), R(Attacking) }
+    let root = app.world_mut().spawn_empty().id();
+    let outer = app.world_mut().spawn((StateChildOf(root), Attacking)).id();
+    let par = app.world_mut().spawn((StateChildOf(outer), Parallel)).id();
+    let l = app.world_mut().spawn((StateChildOf(par), Attacking)).id();
+    let r = app.world_mut().spawn((StateChildOf(par), Attacking)).id();
+    app.world_mut().entity_mut(outer).insert(InitialState(par));
+    app.world_mut().entity_mut(root).insert((InitialState(outer), StateMachine::new()));
+    app.update();
+    app.world_mut().resource_mut::<StateSystemLog>().ticks.clear();
+    app.update();
+
+    let ticks = &app.world().resource::<StateSystemLog>().ticks;
+    assert_eq!(ticks, &vec![StateContext { root, state: l.min(r) }], "one run, with the deepest marked state");
+}
+
+#[derive(Component, Clone, PartialEq, Debug)]
+struct Charges(u32);
+
+#[test]
+fn resettable_restores_initial_value_on_reset() {
+    let mut app = test_app();
+    app.add_resettable::<Charges>();
+
+    // root -> { S (initial), T (Charges 3, Resettable) }; S -> T resets T
+    let root = app.world_mut().spawn_empty().id();
+    let s = app.world_mut().spawn(StateChildOf(root)).id();
+    let t = app.world_mut().spawn((StateChildOf(root), Charges(3), Resettable::<Charges>::new())).id();
+    app.world_mut().spawn((Source(s), Target(t), EventEdge::<TestEvt>::default(), ResetEdge(ResetScope::Target)));
+    app.world_mut().spawn((Source(t), Target(s), EventEdge::<EvtGoBack>::default()));
+    app.world_mut().entity_mut(root).insert((InitialState(s), StateMachine::new()));
+    app.update();
+    assert_eq!(app.world().get::<Resettable<Charges>>(t).unwrap().initial(), Some(&Charges(3)));
+
+    app.world_mut().commands().trigger(TestEvt { target: root });
+    app.update();
+    app.world_mut().get_mut::<Charges>(t).unwrap().0 = 0;
+
+    // Leaving T doesn't reset it
+    app.world_mut().commands().trigger(EvtGoBack { target: root });
+    app.update();
+    assert_eq!(app.world().get::<Charges>(t), Some(&Charges(0)));
+
+    // The reset edge back into T restores the snapshot
+    app.world_mut().commands().trigger(TestEvt { target: root });
+    app.update();
+    assert_eq!(app.world().get::<Charges>(t), Some(&Charges(3)));
+}